@@ -0,0 +1,147 @@
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use rand::Rng;
+
+use crate::{
+    config::AnnouncementConfig,
+    server::Server,
+    template::TemplateContext,
+    time::{parse_time_of_day, system_time_since_unix_epoch_seconds},
+};
+
+static TAG: &str = "announcement";
+
+/// The schedule of a single `[[announcements]]` entry.
+struct AnnouncementSchedule {
+    /// When the interval is next due. `None` if the entry has no interval.
+    next_at: Option<Instant>,
+    /// Times of day in minutes since midnight UTC.
+    times: Vec<u32>,
+    /// The minute since the Unix epoch of the last broadcast at a time of day, so each time only
+    /// fires once.
+    last_time_minute: Option<u64>,
+    /// The index in `pool` of the last message, so it isn't repeated.
+    last_pool_index: Option<usize>,
+}
+
+/// Keeps track of when each `[[announcements]]` entry is due.
+pub struct AnnouncementScheduler {
+    config: Vec<AnnouncementConfig>,
+    schedules: Vec<AnnouncementSchedule>,
+}
+
+impl AnnouncementScheduler {
+    pub fn new(config: &[AnnouncementConfig]) -> Self {
+        let now = Instant::now();
+        let schedules = config
+            .iter()
+            .map(|announcement| AnnouncementSchedule {
+                next_at: Some(announcement.interval_minutes)
+                    .filter(|minutes| *minutes > 0)
+                    .map(|minutes| now + Duration::from_secs(minutes * 60)),
+                times: announcement
+                    .times
+                    .iter()
+                    .filter_map(|time| {
+                        let minute = parse_time_of_day(time);
+                        if minute.is_none() {
+                            warn!(target: TAG, "Ignoring invalid announcement time: time = {time}");
+                        }
+                        minute
+                    })
+                    .collect(),
+                last_time_minute: None,
+                last_pool_index: None,
+            })
+            .collect();
+        if !config.is_empty() {
+            info!(target: TAG, "Scheduled {} announcements", config.len());
+        }
+
+        Self {
+            config: config.to_vec(),
+            schedules,
+        }
+    }
+
+    /// Starts over with a new config, unless the announcements did not change.
+    pub fn set_config(&mut self, config: &[AnnouncementConfig]) {
+        if self.config != config {
+            *self = Self::new(config);
+        }
+    }
+
+    /// Returns the message templates of the announcements which are due. Announcements which are
+    /// due while there are fewer than `min_players` players are skipped.
+    fn take_due(&mut self, now: Instant, unix_minute: u64, players: usize) -> Vec<String> {
+        let mut due = vec![];
+        for (announcement, schedule) in self.config.iter().zip(self.schedules.iter_mut()) {
+            let mut is_due = false;
+            if let Some(next_at) = schedule.next_at {
+                if now >= next_at {
+                    is_due = true;
+                    schedule.next_at =
+                        Some(now + Duration::from_secs(announcement.interval_minutes * 60));
+                }
+            }
+            let minute_of_day = (unix_minute % (24 * 60)) as u32;
+            if schedule.times.contains(&minute_of_day)
+                && schedule.last_time_minute != Some(unix_minute)
+            {
+                is_due = true;
+                schedule.last_time_minute = Some(unix_minute);
+            }
+            if !is_due || players < announcement.min_players {
+                continue;
+            }
+
+            if announcement.pool.is_empty() {
+                due.push(announcement.message.clone());
+            } else {
+                let index = pick_pool_index(announcement.pool.len(), schedule.last_pool_index);
+                schedule.last_pool_index = Some(index);
+                due.push(announcement.pool[index].clone());
+            }
+        }
+
+        due
+    }
+}
+
+/// Picks a random index into a pool of the given size, avoiding the last pick if possible.
+fn pick_pool_index(len: usize, last: Option<usize>) -> usize {
+    let mut rng = rand::thread_rng();
+    match last {
+        Some(last) if len > 1 => {
+            // Pick from the other indexes by skipping over the last one.
+            let index = rng.gen_range(0..len - 1);
+            if index >= last {
+                index + 1
+            } else {
+                index
+            }
+        }
+        _ => rng.gen_range(0..len),
+    }
+}
+
+/// Broadcasts the announcements which are due.
+pub fn on_update(server: &mut Server) {
+    let players = server.players.listed().len();
+    let unix_minute = system_time_since_unix_epoch_seconds() / 60;
+    let due = server
+        .announcements
+        .take_due(Instant::now(), unix_minute, players);
+    if due.is_empty() {
+        return;
+    }
+
+    let context = TemplateContext::new(server);
+    for template in due {
+        let message = context.render(&template);
+        if !message.is_empty() {
+            server.broadcast_chat_message(&message);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use log::warn;
+use serde::Serialize;
+
+use crate::{
+    config::AuditLogConfig,
+    time::{format_rfc3339_utc, format_utc_date, system_time_since_unix_epoch_seconds},
+};
+
+static TAG: &str = "audit";
+
+/// An entry in the audit log. SteamIds are raw u64 and names are Steam persona names at the time of
+/// the event.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    Chat {
+        steam_id: u64,
+        name: String,
+        message: String,
+    },
+    Command {
+        steam_id: u64,
+        name: String,
+        command: String,
+        args: Vec<String>,
+        /// `None` if the command succeeded, otherwise the error sent back to the player.
+        error: Option<String>,
+    },
+    Join {
+        steam_id: u64,
+        name: String,
+    },
+    Leave {
+        steam_id: u64,
+        name: String,
+    },
+    Kick {
+        steam_id: u64,
+        name: String,
+    },
+    Ban {
+        steam_id: u64,
+        name: String,
+        reason: Option<String>,
+        expires_at: Option<u64>,
+    },
+    Unban {
+        steam_id: u64,
+    },
+    Mute {
+        steam_id: u64,
+        name: String,
+        reason: Option<String>,
+        expires_at: Option<u64>,
+    },
+    Unmute {
+        steam_id: u64,
+    },
+    Filter {
+        steam_id: u64,
+        name: String,
+        rule: String,
+        action: String,
+        message: String,
+    },
+    VoteKick {
+        initiator: u64,
+        target: u64,
+        reason: Option<String>,
+        /// One of `started`, `passed`, `failed` or `cancelled`.
+        outcome: String,
+    },
+}
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    timestamp: String,
+    #[serde(flatten)]
+    event: &'a AuditEvent,
+}
+
+/// An append-only audit log of chat and moderation events. Each entry is written as a JSON line to
+/// a file named after the current UTC date. When a file grows past the size cap, the log continues
+/// in a new numbered part for the same day.
+pub struct AuditLog {
+    config: AuditLogConfig,
+    file: Option<File>,
+    date: String,
+    part: u32,
+    size: u64,
+}
+
+impl AuditLog {
+    pub fn new(config: &AuditLogConfig) -> Self {
+        Self {
+            config: config.clone(),
+            file: None,
+            date: String::new(),
+            part: 0,
+            size: 0,
+        }
+    }
+
+    /// Applies a new config. The current file is closed and reopened on the next record.
+    pub fn set_config(&mut self, config: &AuditLogConfig) {
+        self.config = config.clone();
+        self.file = None;
+        self.date = String::new();
+    }
+
+    pub fn record(&mut self, event: AuditEvent) {
+        if !self.config.enabled {
+            return;
+        }
+        let now = system_time_since_unix_epoch_seconds();
+        let entry = AuditEntry {
+            timestamp: format_rfc3339_utc(now),
+            event: &event,
+        };
+        let mut line = match serde_json::to_string(&entry) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!(target: TAG, "Failed serializing audit entry: error = {e}");
+                return;
+            }
+        };
+        line.push('\n');
+
+        let date = format_utc_date(now);
+        if date != self.date {
+            self.date = date;
+            self.part = 0;
+            self.file = None;
+        }
+        if self.config.max_file_bytes > 0
+            && self.size + line.len() as u64 > self.config.max_file_bytes
+        {
+            self.part += 1;
+            self.file = None;
+        }
+        if self.file.is_none() {
+            self.open();
+        }
+
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        match file.write_all(line.as_bytes()) {
+            Ok(()) => self.size += line.len() as u64,
+            Err(e) => warn!(target: TAG, "Failed writing audit entry: error = {e}"),
+        }
+    }
+
+    /// Opens the file for the current date and part. Parts which are already full are skipped, so
+    /// a restart continues in the latest part.
+    fn open(&mut self) {
+        if let Err(e) = fs::create_dir_all(&self.config.directory) {
+            warn!(
+                target: TAG,
+                "Failed creating audit log directory: directory = {}, error = {e}",
+                self.config.directory
+            );
+            return;
+        }
+        loop {
+            let path = self.path();
+            let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            if self.config.max_file_bytes > 0 && size >= self.config.max_file_bytes {
+                self.part += 1;
+                continue;
+            }
+            match OpenOptions::new().create(true).append(true).open(&path) {
+                Ok(file) => {
+                    self.file = Some(file);
+                    self.size = size;
+                }
+                Err(e) => warn!(
+                    target: TAG,
+                    "Failed opening audit log: path = {}, error = {e}",
+                    path.display()
+                ),
+            }
+            return;
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        let file_name = if self.part == 0 {
+            format!("audit-{}.jsonl", self.date)
+        } else {
+            format!("audit-{}.{}.jsonl", self.date, self.part)
+        };
+
+        PathBuf::from(&self.config.directory).join(file_name)
+    }
+}
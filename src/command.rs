@@ -1,9 +1,104 @@
 use steamworks::SteamId;
 
+use crate::{audit::AuditEvent, game::Game, server::Server};
+
 pub mod handler;
 
+/// Where a command was run from. This decides where replies are sent.
+#[derive(Clone, Copy, PartialEq)]
+pub enum CommandSource {
+    /// A chat message from a player in the lobby.
+    Chat,
+    /// A line typed into the host's console. The sender is the host SteamId.
+    Console,
+}
+
 pub struct CommandContext<'a> {
     pub sender: SteamId,
+    pub source: CommandSource,
     pub command: &'a str,
-    pub _args: Vec<&'a str>,
+    pub args: Vec<&'a str>,
+}
+
+impl CommandContext<'_> {
+    /// Parses a command line such as `!kick 123`. Returns `None` if the line is not a command.
+    pub fn parse(sender: SteamId, source: CommandSource, line: &str) -> Option<CommandContext<'_>> {
+        let mut words = line.strip_prefix('!')?.split_whitespace();
+        let command = words.next()?;
+
+        Some(CommandContext {
+            sender,
+            source,
+            command,
+            args: words.collect(),
+        })
+    }
+
+    /// Sends a reply to whoever ran the command.
+    pub fn reply(&self, server: &Server, message: &str) {
+        reply(server, self.source, &self.sender, message);
+    }
+}
+
+/// The result of a command handler. An `Err` holds a message which is sent back to the sender.
+pub type CommandResult = Result<(), String>;
+
+/// Runs a command and records it in the audit log. If the command fails, the error is sent back to
+/// the sender. Returns `false` if there is no command with the given name.
+pub fn run_command(server: &mut Server, game: &mut Game, command_ctx: CommandContext) -> bool {
+    let Some(handler) = handler::resolve_handler(&command_ctx) else {
+        return false;
+    };
+    let (sender, source) = (command_ctx.sender, command_ctx.source);
+    let command = command_ctx.command.to_owned();
+    let args: Vec<String> = command_ctx.args.iter().map(|arg| arg.to_string()).collect();
+
+    let result = handler(server, game, command_ctx);
+    server.record_event(AuditEvent::Command {
+        steam_id: sender.raw(),
+        name: server.persona_name(&sender),
+        command,
+        args,
+        error: result.as_ref().err().cloned(),
+    });
+    if let Err(error) = result {
+        reply(server, source, &sender, &error);
+    }
+
+    true
+}
+
+fn reply(server: &Server, source: CommandSource, sender: &SteamId, message: &str) {
+    match source {
+        CommandSource::Chat => server.send_chat_message(sender, message),
+        CommandSource::Console => println!("[console] {message}"),
+    }
+}
+
+/// Returns an error if the sender of the command is not an admin.
+pub fn require_admin(server: &Server, command_ctx: &CommandContext) -> CommandResult {
+    if server.is_admin(&command_ctx.sender) {
+        Ok(())
+    } else {
+        Err("You do not have permission to use this command.".to_owned())
+    }
+}
+
+/// Parses a command argument which refers to a player, either a raw SteamId or a `#` followed by
+/// the player's number in `!players`.
+pub fn parse_target(server: &Server, arg: Option<&&str>) -> Result<SteamId, String> {
+    let Some(arg) = arg else {
+        return Err("Missing player.".to_owned());
+    };
+    if let Some(number) = arg.strip_prefix('#') {
+        return number
+            .parse::<u32>()
+            .ok()
+            .and_then(|number| server.players.get_by_number(number))
+            .map(|player| player.steam_id)
+            .ok_or(format!("No player #{number}. Type !players to see the list."));
+    }
+    arg.parse::<u64>()
+        .map(SteamId::from_raw)
+        .map_err(|_| format!("Unknown player: {arg}"))
 }
@@ -1,19 +1,40 @@
 use crate::{game::Game, server::Server};
 
-use super::CommandContext;
+use super::{CommandContext, CommandResult};
 
+mod ban;
+mod event;
 mod help;
+mod kick;
+mod mute;
+mod players;
 mod rain;
+mod tempban;
+mod unban;
+mod unmute;
+mod vote;
+mod votekick;
 
 /// Returns the handler function for the given command and args.
 pub fn resolve_handler(
     command_ctx: &CommandContext,
-) -> Option<fn(&mut Server, &mut Game, CommandContext)> {
+) -> Option<fn(&mut Server, &mut Game, CommandContext) -> CommandResult> {
     // TODO: Map these with some registry system? Would be very useful for user-defined commands,
     //  and would make it easier to create and manage new commands later.
     match command_ctx.command {
         "help" | "commands" => Some(help::handle),
+        "players" | "who" => Some(players::handle),
         "rain" => Some(rain::handle),
+        "event" => Some(event::handle),
+        "kick" => Some(kick::handle),
+        "ban" => Some(ban::handle),
+        "tempban" => Some(tempban::handle),
+        "unban" => Some(unban::handle),
+        "mute" => Some(mute::handle),
+        "unmute" => Some(unmute::handle),
+        "votekick" => Some(votekick::handle),
+        "yes" => Some(vote::handle_yes),
+        "no" => Some(vote::handle_no),
         _ => None,
     }
 }
@@ -0,0 +1,21 @@
+use crate::{
+    command::{parse_target, require_admin, CommandContext, CommandResult},
+    game::Game,
+    moderation::ban_player,
+    Server,
+};
+
+/// `!ban <player> [reason]`
+pub fn handle(server: &mut Server, game: &mut Game, command_ctx: CommandContext) -> CommandResult {
+    require_admin(server, &command_ctx)?;
+    let target = parse_target(server, command_ctx.args.first())?;
+    if server.is_admin(&target) {
+        return Err("You cannot ban an admin.".to_owned());
+    }
+    let reason = Some(command_ctx.args[1..].join(" ")).filter(|reason| !reason.is_empty());
+
+    ban_player(server, game, &target, reason, None);
+    command_ctx.reply(server, format!("Banned {}.", target.raw()).as_str());
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use crate::{
+    command::{require_admin, CommandContext, CommandResult},
+    game::Game,
+    time::{format_duration, parse_duration},
+    Server,
+};
+
+static USAGE: &str = "Usage: !event <profile> <duration, e.g. 30m, 2h>, or !event end";
+
+/// `!event [<profile> <duration> | end]`
+pub fn handle(server: &mut Server, game: &mut Game, command_ctx: CommandContext) -> CommandResult {
+    let Some(profile) = command_ctx.args.first() else {
+        let status = match game.spawn_manager.event() {
+            Some(event) => format!(
+                "Current event: {}, ends in {}.",
+                event.profile,
+                format_duration(event.ends_at.saturating_duration_since(game.clock.now()))
+            ),
+            None => "No event is running.".to_owned(),
+        };
+        command_ctx.reply(server, &status);
+        if server.is_admin(&command_ctx.sender) {
+            let mut profiles: Vec<&str> = server
+                .config
+                .spawn_profiles
+                .keys()
+                .map(String::as_str)
+                .collect();
+            profiles.sort_unstable();
+            command_ctx.reply(server, &format!("Profiles: {}", profiles.join(", ")));
+        }
+        return Ok(());
+    };
+    require_admin(server, &command_ctx)?;
+
+    if *profile == "end" || *profile == "stop" {
+        if !game.spawn_manager.end_event(server) {
+            return Err("No event is running.".to_owned());
+        }
+        command_ctx.reply(server, "Ended the event.");
+        return Ok(());
+    }
+
+    let Some(duration) = command_ctx.args.get(1).and_then(|arg| parse_duration(arg)) else {
+        return Err(USAGE.to_owned());
+    };
+    game.spawn_manager.start_event(server, profile, duration)?;
+    command_ctx.reply(
+        server,
+        &format!("Started {profile} for {}.", format_duration(duration)),
+    );
+
+    Ok(())
+}
@@ -1,5 +1,17 @@
-use crate::{command::CommandContext, game::Game, Server};
+use crate::{
+    command::{CommandContext, CommandResult},
+    game::Game,
+    Server,
+};
 
-pub fn handle(server: &mut Server, _game: &mut Game, command_ctx: CommandContext) {
-    server.send_chat_message(&command_ctx.sender, "Available commands: !help, !rain")
+pub fn handle(server: &mut Server, _game: &mut Game, command_ctx: CommandContext) -> CommandResult {
+    command_ctx.reply(server, "Available commands: !help, !players, !rain, !event, !votekick");
+    if server.is_admin(&command_ctx.sender) {
+        command_ctx.reply(
+            server,
+            "Admin commands: !kick, !ban, !tempban, !unban, !mute, !unmute, !event <profile>",
+        );
+    }
+
+    Ok(())
 }
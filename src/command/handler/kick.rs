@@ -0,0 +1,23 @@
+use crate::{
+    command::{parse_target, require_admin, CommandContext, CommandResult},
+    game::Game,
+    moderation::kick_player,
+    Server,
+};
+
+/// `!kick <player>`
+pub fn handle(server: &mut Server, game: &mut Game, command_ctx: CommandContext) -> CommandResult {
+    require_admin(server, &command_ctx)?;
+    let target = parse_target(server, command_ctx.args.first())?;
+    if !server.players.contains(&target) {
+        return Err(format!("Player is not in the lobby: {}", target.raw()));
+    }
+    if server.is_admin(&target) {
+        return Err("You cannot kick an admin.".to_owned());
+    }
+
+    kick_player(server, game, &target);
+    command_ctx.reply(server, format!("Kicked {}.", target.raw()).as_str());
+
+    Ok(())
+}
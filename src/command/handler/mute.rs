@@ -0,0 +1,28 @@
+use crate::{
+    command::{parse_target, require_admin, CommandContext, CommandResult},
+    game::Game,
+    moderation::mute_player,
+    time::{format_duration, parse_duration},
+    Server,
+};
+
+/// `!mute <player> [duration] [reason]`
+pub fn handle(server: &mut Server, game: &mut Game, command_ctx: CommandContext) -> CommandResult {
+    require_admin(server, &command_ctx)?;
+    let target = parse_target(server, command_ctx.args.first())?;
+    if server.is_admin(&target) {
+        return Err("You cannot mute an admin.".to_owned());
+    }
+    let duration = command_ctx.args.get(1).and_then(|arg| parse_duration(arg));
+    let reason_start = if duration.is_some() { 2 } else { 1 };
+    let reason = Some(command_ctx.args[reason_start..].join(" ")).filter(|r| !r.is_empty());
+
+    mute_player(server, game, &target, reason, duration);
+    let reply = match duration {
+        Some(duration) => format!("Muted {} for {}.", target.raw(), format_duration(duration)),
+        None => format!("Muted {}.", target.raw()),
+    };
+    command_ctx.reply(server, &reply);
+
+    Ok(())
+}
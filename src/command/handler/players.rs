@@ -0,0 +1,59 @@
+use crate::{
+    command::{CommandContext, CommandResult},
+    game::Game,
+    time::format_duration,
+    Server,
+};
+
+static PLAYERS_PER_PAGE: usize = 5;
+/// Names are cut to this many characters so each entry fits on one chat line.
+static MAX_NAME_CHARS: usize = 16;
+
+/// `!players [page]`
+pub fn handle(server: &mut Server, game: &mut Game, command_ctx: CommandContext) -> CommandResult {
+    let players = server.players.listed();
+    if players.is_empty() {
+        command_ctx.reply(server, "Nobody else is here.");
+        return Ok(());
+    }
+
+    let page_count = players.len().div_ceil(PLAYERS_PER_PAGE);
+    let page = match command_ctx.args.first() {
+        Some(arg) => arg
+            .parse::<usize>()
+            .ok()
+            .filter(|page| (1..=page_count).contains(page))
+            .ok_or(format!("Invalid page. There are {page_count} pages."))?,
+        None => 1,
+    };
+
+    let mut lines = vec![format!(
+        "Players ({}/{}), page {page}/{page_count}:",
+        players.len(),
+        server.config.max_players
+    )];
+    let start = (page - 1) * PLAYERS_PER_PAGE;
+    for player in players.iter().skip(start).take(PLAYERS_PER_PAGE) {
+        let zone = game
+            .actor_manager
+            .get_player_actor(&player.steam_id)
+            .map(|actor| actor.zone.as_str())
+            .filter(|zone| !zone.is_empty())
+            .unwrap_or("?");
+        let name: String = player.name.chars().take(MAX_NAME_CHARS).collect();
+        lines.push(format!(
+            "#{} {name} - {zone}, {}",
+            player.number,
+            format_duration(player.session_duration())
+        ));
+    }
+    if page < page_count {
+        lines.push(format!("Type !players {} for more.", page + 1));
+    }
+
+    for line in lines {
+        command_ctx.reply(server, &line);
+    }
+
+    Ok(())
+}
@@ -1,73 +1,128 @@
-use std::time::Instant;
+use log::info;
 
 use crate::{
-    command::CommandContext,
+    command::{CommandContext, CommandResult},
     game::{actor::ActorType, Game},
+    time::format_duration,
     Server,
 };
 
 static TAG: &str = "rain";
 
-pub fn handle(server: &mut Server, game: &mut Game, command_ctx: CommandContext) {
+pub fn handle(server: &mut Server, game: &mut Game, command_ctx: CommandContext) -> CommandResult {
     let Some(player_actor) = game.actor_manager.get_player_actor(&command_ctx.sender) else {
-        server.send_chat_message(&command_ctx.sender, "Failed. No Player character found.");
-        return;
+        return Err("Failed. No Player character found.".to_owned());
     };
 
-    // TODO: We should actually check if the server host SteamId can create the actor
+    let host_id = server.host_steam_id;
     if !game
         .actor_manager
-        .user_can_create_actor(&command_ctx.sender, true, &ActorType::Raincloud)
+        .host_can_create_actor(&host_id, &ActorType::Raincloud)
     {
-        server.send_chat_message(&command_ctx.sender, "Failed. You have too many props!");
-        println!(
-            "[{TAG}] User cannot create raincloud actor: steam_id = {}",
+        info!(
+            target: TAG,
+            "Host cannot create raincloud actor: sender = {}",
             command_ctx.sender.raw()
         );
-        return;
+        return Err("Failed. There are too many actors!".to_owned());
     }
 
     let spawn_manager = &mut game.spawn_manager;
-    if !spawn_manager.can_spawn_user_actor(&ActorType::Raincloud) {
+    if !spawn_manager.can_spawn_user_actor(&server.config, &ActorType::Raincloud) {
         let next_raincloud_instant = spawn_manager.next_user_spawn_instant(&ActorType::Raincloud);
         if let Some(next_raincloud_instant) = next_raincloud_instant {
-            let _ = server.send_chat_message(
-                &command_ctx.sender,
-                format!(
-                    "Someone already spawned a rain cloud. Please wait {}.",
-                    format_timeout_from_now(next_raincloud_instant)
-                )
-                .as_str(),
-            );
+            return Err(format!(
+                "Someone already spawned a rain cloud. Please wait {}.",
+                format_duration(next_raincloud_instant.saturating_duration_since(game.clock.now()))
+            ));
         } else {
-            let _ = server.send_chat_message(
-                &command_ctx.sender,
-                "Someone already spawned a rain cloud. Please wait for it to despawn.",
+            return Err(
+                "Someone already spawned a rain cloud. Please wait for it to despawn.".to_owned(),
             );
         }
-        return;
     }
+    let zone = player_actor.zone.clone();
     let mut raincloud_position = player_actor.position.clone();
     raincloud_position.y = 42.0;
     spawn_manager.spawn_user_raincloud(
         server,
         &mut game.actor_manager,
-        "main_zone",
+        &mut game.rng,
+        &zone,
         &raincloud_position,
     );
 
-    server.send_chat_message(&command_ctx.sender, "Spawned rain cloud.");
+    command_ctx.reply(server, "Spawned rain cloud.");
+
+    Ok(())
 }
 
-fn format_timeout_from_now(instant: &Instant) -> String {
-    let duration = instant.duration_since(Instant::now());
-    let secs = duration.as_secs();
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use steamworks::SteamId;
+
+    use crate::{
+        command::{CommandContext, CommandSource},
+        config::Config,
+        game::{
+            actor::{Actor, ActorType},
+            clock::Clock,
+            Game,
+        },
+        packet::variant::Vector3,
+        simulation::{headless_server, remove_store_dir, temp_store_dir},
+        Server,
+    };
+
+    #[test]
+    fn rain_has_a_cooldown() {
+        let store_dir = temp_store_dir("test-rain-cooldown");
+        let mut config = Config::default();
+        // Keeps the rainclouds on the map, so only their lifetime despawns them.
+        config.spawns.raincloud_speed = 0.0;
+        let (mut server, _) = headless_server(config, &store_dir);
+        let mut game = Game::new(&server.config, Clock::manual());
+        let sender = SteamId::from_raw(76561197960265729);
+        game.actor_manager.insert_actor(Actor {
+            id: 1,
+            creator_id: sender,
+            actor_type: ActorType::Player,
+            zone: "main_zone".to_owned(),
+            zone_owner: -1,
+            position: Vector3 {
+                x: 30.0,
+                y: 0.0,
+                z: -50.0,
+            },
+            rotation: Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+        });
+        let rain = |server: &mut Server, game: &mut Game| {
+            let command_ctx = CommandContext::parse(sender, CommandSource::Chat, "!rain").unwrap();
+            super::handle(server, game, command_ctx)
+        };
+
+        // `[spawns.limits]` allows two user rainclouds by default.
+        assert!(rain(&mut server, &mut game).is_ok());
+        assert!(rain(&mut server, &mut game).is_ok());
+        let error = rain(&mut server, &mut game).unwrap_err();
+        assert!(error.contains("Please wait"), "{error}");
+
+        game.clock.advance(Duration::from_secs(60));
+        game.on_update(&mut server);
+        assert!(rain(&mut server, &mut game).is_err());
+
+        // The rainclouds despawn once their 541 second lifetime is over.
+        game.clock.advance(Duration::from_secs(500));
+        game.on_update(&mut server);
+        assert!(rain(&mut server, &mut game).is_ok());
 
-    if secs < 60 {
-        format!("{}s", secs)
-    } else if secs < 3600 {
-        format!("{}m {}s", secs / 60, secs % 60)
-    } else {
-        format!("{}h {}m {}s", secs / 3600, (secs % 3600) / 60, secs % 60)
+        drop(server);
+        remove_store_dir(&store_dir);
     }
 }
@@ -0,0 +1,28 @@
+use crate::{
+    command::{parse_target, require_admin, CommandContext, CommandResult},
+    game::Game,
+    moderation::ban_player,
+    time::{format_duration, parse_duration},
+    Server,
+};
+
+/// `!tempban <player> <duration> [reason]`
+pub fn handle(server: &mut Server, game: &mut Game, command_ctx: CommandContext) -> CommandResult {
+    require_admin(server, &command_ctx)?;
+    let target = parse_target(server, command_ctx.args.first())?;
+    let Some(duration) = command_ctx.args.get(1).and_then(|arg| parse_duration(arg)) else {
+        return Err("Usage: !tempban <player> <duration, e.g. 30m, 2h, 7d> [reason]".to_owned());
+    };
+    if server.is_admin(&target) {
+        return Err("You cannot ban an admin.".to_owned());
+    }
+    let reason = Some(command_ctx.args[2..].join(" ")).filter(|reason| !reason.is_empty());
+
+    ban_player(server, game, &target, reason, Some(duration));
+    command_ctx.reply(
+        server,
+        format!("Banned {} for {}.", target.raw(), format_duration(duration)).as_str(),
+    );
+
+    Ok(())
+}
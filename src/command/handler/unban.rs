@@ -0,0 +1,25 @@
+use crate::{
+    command::{parse_target, require_admin, CommandContext, CommandResult},
+    game::Game,
+    moderation::unban_player,
+    Server,
+};
+
+/// `!unban <player>`
+pub fn handle(server: &mut Server, _game: &mut Game, command_ctx: CommandContext) -> CommandResult {
+    require_admin(server, &command_ctx)?;
+    let target = parse_target(server, command_ctx.args.first())?;
+    if server.ban_list.is_config_ban(&target.raw()) {
+        return Err(format!(
+            "{} is banned in the config. Remove them from ban_list to unban.",
+            target.raw()
+        ));
+    }
+    if unban_player(server, &target).is_none() {
+        return Err(format!("Player is not banned: {}", target.raw()));
+    }
+
+    command_ctx.reply(server, format!("Unbanned {}.", target.raw()).as_str());
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use crate::{
+    command::{parse_target, require_admin, CommandContext, CommandResult},
+    game::Game,
+    moderation::unmute_player,
+    Server,
+};
+
+/// `!unmute <player>`
+pub fn handle(server: &mut Server, _game: &mut Game, command_ctx: CommandContext) -> CommandResult {
+    require_admin(server, &command_ctx)?;
+    let target = parse_target(server, command_ctx.args.first())?;
+    if unmute_player(server, &target).is_none() {
+        return Err(format!("Player is not muted: {}", target.raw()));
+    }
+
+    command_ctx.reply(server, format!("Unmuted {}.", target.raw()).as_str());
+
+    Ok(())
+}
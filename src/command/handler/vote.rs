@@ -0,0 +1,24 @@
+use crate::{
+    command::{CommandContext, CommandResult},
+    game::Game,
+    moderation::cast_vote_kick,
+    Server,
+};
+
+/// `!yes`
+pub fn handle_yes(
+    server: &mut Server,
+    game: &mut Game,
+    command_ctx: CommandContext,
+) -> CommandResult {
+    cast_vote_kick(server, game, &command_ctx.sender, true)
+}
+
+/// `!no`
+pub fn handle_no(
+    server: &mut Server,
+    game: &mut Game,
+    command_ctx: CommandContext,
+) -> CommandResult {
+    cast_vote_kick(server, game, &command_ctx.sender, false)
+}
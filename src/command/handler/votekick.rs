@@ -0,0 +1,14 @@
+use crate::{
+    command::{parse_target, CommandContext, CommandResult},
+    game::Game,
+    moderation::start_vote_kick,
+    Server,
+};
+
+/// `!votekick <player> [reason]`
+pub fn handle(server: &mut Server, game: &mut Game, command_ctx: CommandContext) -> CommandResult {
+    let target = parse_target(server, command_ctx.args.first())?;
+    let reason = Some(command_ctx.args[1..].join(" ")).filter(|reason| !reason.is_empty());
+
+    start_vote_kick(server, game, &command_ctx.sender, &target, reason)
+}
@@ -1,16 +1,31 @@
-use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    time::{Duration, SystemTime},
+};
 
-use crate::random::lobby_code;
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    game::actor::{type_info::actor_types, ActorType},
+    logger::LogFormat,
+    moderation::filter::FilterAction,
+    player::Role,
+    random::lobby_code,
+};
+
+pub static CONFIG_PATH: &str = "config.toml";
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Config {
     #[serde(default = "default_name")]
     pub name: String,
-    #[serde(default = "default_motd")]
-    pub motd: String,
+    #[serde(default)]
+    pub motd: MotdConfig,
     #[serde(default = "default_game_version")]
     pub game_version: String,
-    #[serde(default = "default_lobby_code")]
+    /// Empty in a freshly parsed config if the file doesn't set one, see `Config::load`.
+    #[serde(default)]
     pub lobby_code: String,
     #[serde(default = "default_max_players")]
     pub max_players: u32,
@@ -34,9 +49,104 @@ pub struct Config {
     pub tag_modded: bool,
     #[serde(default = "default_ban_list")]
     pub ban_list: Vec<u64>,
+    /// Path to the JSON file where bans issued through commands are persisted.
+    #[serde(default = "default_ban_file")]
+    pub ban_file: String,
+    /// SteamIds (as raw u64) which are allowed to use moderation commands.
+    #[serde(default = "default_admins")]
+    pub admins: Vec<u64>,
+    /// SteamIds (as raw u64) which get the `trusted` role, such as for higher prop limits.
+    #[serde(default)]
+    pub trusted: Vec<u64>,
+    /// Path to the JSON file where mutes and past mute offenses are persisted.
+    #[serde(default = "default_mute_file")]
+    pub mute_file: String,
+    /// Whether to tell everyone in the lobby when a player is muted.
+    #[serde(default = "default_mute_announce")]
+    pub mute_announce: bool,
+    /// Number of mutes within 24 hours after which a player is tempbanned instead. 0 disables
+    /// escalation.
+    #[serde(default = "default_mute_escalation_offenses")]
+    pub mute_escalation_offenses: u32,
+    #[serde(default = "default_mute_escalation_tempban_minutes")]
+    pub mute_escalation_tempban_minutes: u64,
+    /// Path to the JSON file where the first and last visit of every player is persisted.
+    #[serde(default = "default_seen_file")]
+    pub seen_file: String,
+    /// Used for `{rules_url}` in message templates.
+    #[serde(default)]
+    pub rules_url: String,
+    #[serde(default)]
+    pub chat_filter: ChatFilterConfig,
+    #[serde(default)]
+    pub vote_kick: VoteKickConfig,
+    #[serde(default)]
+    pub join_leave: JoinLeaveConfig,
+    #[serde(default)]
+    pub announcements: Vec<AnnouncementConfig>,
+    /// Seeds the random number generator for spawns and actor IDs. A random seed is used if unset.
+    /// Changes only take effect after a restart.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub spawns: SpawnsConfig,
+    #[serde(
+        default = "default_spawn_profiles",
+        deserialize_with = "deserialize_spawn_profiles"
+    )]
+    pub spawn_profiles: HashMap<String, SpawnProfileConfig>,
+    #[serde(default)]
+    pub spawn_schedule: Vec<SpawnScheduleConfig>,
+    #[serde(default)]
+    pub props: PropsConfig,
+    #[serde(default)]
+    pub audit_log: AuditLogConfig,
+    #[serde(default)]
+    pub http_api: HttpApiConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
 }
 
 impl Config {
+    /// Reads and parses the config file at `CONFIG_PATH`. If the file doesn't set `lobby_code`, the
+    /// given code is kept, or a random one is picked if there is none yet.
+    pub fn load(current_lobby_code: Option<&str>) -> io::Result<Config> {
+        let mut config: Config = toml::from_str(&fs::read_to_string(CONFIG_PATH)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        if config.lobby_code.is_empty() {
+            config.lobby_code = current_lobby_code
+                .map(str::to_owned)
+                .unwrap_or_else(default_lobby_code);
+        }
+        config
+            .validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        Ok(config)
+    }
+
+    /// Checks `[spawns]` and the spawn profiles applied on top of it.
+    fn validate(&self) -> Result<(), String> {
+        self.spawns
+            .validate()
+            .map_err(|e| format!("[spawns]: {e}"))?;
+        let mut profiles: Vec<_> = self.spawn_profiles.iter().collect();
+        profiles.sort_unstable_by_key(|(name, _)| *name);
+        for (name, profile) in profiles {
+            self.spawns
+                .with_profile(profile)
+                .validate()
+                .map_err(|e| format!("[spawn_profiles.{name}]: {e}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns when the config file at `CONFIG_PATH` was last modified.
+    pub fn modified_time() -> Option<SystemTime> {
+        fs::metadata(CONFIG_PATH).and_then(|m| m.modified()).ok()
+    }
+
     pub fn get_lobby_data_for_bool(value: bool) -> String {
         if value { "1".to_owned() } else { "0".to_owned() }
     }
@@ -46,7 +156,7 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             name: default_name(),
-            motd: default_motd(),
+            motd: MotdConfig::default(),
             game_version: default_game_version(),
             lobby_code: default_lobby_code(),
             max_players: default_max_players(),
@@ -60,6 +170,670 @@ impl Default for Config {
             tag_mature: default_tag_generic(),
             tag_modded: default_tag_modded(),
             ban_list: default_ban_list(),
+            ban_file: default_ban_file(),
+            admins: default_admins(),
+            trusted: vec![],
+            mute_file: default_mute_file(),
+            mute_announce: default_mute_announce(),
+            mute_escalation_offenses: default_mute_escalation_offenses(),
+            mute_escalation_tempban_minutes: default_mute_escalation_tempban_minutes(),
+            seen_file: default_seen_file(),
+            rules_url: String::new(),
+            chat_filter: ChatFilterConfig::default(),
+            vote_kick: VoteKickConfig::default(),
+            join_leave: JoinLeaveConfig::default(),
+            announcements: vec![],
+            seed: None,
+            spawns: SpawnsConfig::default(),
+            spawn_profiles: default_spawn_profiles(),
+            spawn_schedule: vec![],
+            props: PropsConfig::default(),
+            audit_log: AuditLogConfig::default(),
+            http_api: HttpApiConfig::default(),
+            logging: LoggingConfig::default(),
+        }
+    }
+}
+
+/// The `[chat_filter]` section. Every rule has an `action` which defaults to `off`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ChatFilterConfig {
+    /// How long the `mute` action mutes for, in minutes. 0 mutes permanently.
+    pub mute_minutes: u64,
+    /// How long the `ban` action bans for, in minutes. 0 bans permanently.
+    pub ban_minutes: u64,
+    /// Blocks messages containing any of the listed words, ignoring case.
+    pub words: ListRuleConfig,
+    /// Blocks messages matching any of the listed regular expressions.
+    pub patterns: ListRuleConfig,
+    pub spam: SpamRuleConfig,
+    pub caps: CapsRuleConfig,
+    pub links: LinksRuleConfig,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ListRuleConfig {
+    pub action: FilterAction,
+    pub list: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SpamRuleConfig {
+    pub action: FilterAction,
+    /// How many times the same message may be sent within the window.
+    pub repeat_count: usize,
+    /// How many messages may be sent within the window.
+    pub message_count: usize,
+    pub window_secs: u64,
+}
+
+impl Default for SpamRuleConfig {
+    fn default() -> Self {
+        Self {
+            action: FilterAction::default(),
+            repeat_count: 3,
+            message_count: 8,
+            window_secs: 10,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct CapsRuleConfig {
+    pub action: FilterAction,
+    /// Messages with fewer letters than this are never considered excessive caps.
+    pub min_letters: usize,
+    /// The ratio of uppercase letters at which a message is considered excessive caps.
+    pub ratio: f64,
+}
+
+impl Default for CapsRuleConfig {
+    fn default() -> Self {
+        Self {
+            action: FilterAction::default(),
+            min_letters: 12,
+            ratio: 0.7,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LinksRuleConfig {
+    pub action: FilterAction,
+    /// Domains which may be linked, e.g. `steamcommunity.com`.
+    pub allowed_domains: Vec<String>,
+}
+
+/// The `[vote_kick]` section.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct VoteKickConfig {
+    pub enabled: bool,
+    /// The share of players, excluding the host and the target, who must vote yes.
+    pub quorum: f64,
+    /// How long a vote runs before it fails.
+    pub duration_secs: u64,
+    /// How long a player has to wait before starting another vote.
+    pub initiator_cooldown_secs: u64,
+    /// How long after a vote ends before another vote can target the same player.
+    pub target_cooldown_secs: u64,
+    /// The minimum number of players in the lobby, excluding the host, to start a vote.
+    pub min_players: usize,
+}
+
+impl Default for VoteKickConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            quorum: 0.5,
+            duration_secs: 60,
+            initiator_cooldown_secs: 300,
+            target_cooldown_secs: 600,
+            min_players: 3,
+        }
+    }
+}
+
+/// The MOTD sent to players when they join. It can be a single message, `motd = "..."`, or a
+/// `[motd]` section. Messages are templates, see `TemplateContext`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(from = "MotdConfigValue")]
+pub struct MotdConfig {
+    /// Sent to players who don't match a more specific list below.
+    pub messages: Vec<String>,
+    /// Sent instead of `messages` on a player's first visit.
+    pub first_time: Vec<String>,
+    /// Sent instead of `messages` to players who have been in the lobby before.
+    pub returning: Vec<String>,
+    /// Sent instead of any other list to players with the given role.
+    pub roles: HashMap<Role, Vec<String>>,
+    /// The delay between each message.
+    pub delay_ms: u64,
+}
+
+impl Default for MotdConfig {
+    fn default() -> Self {
+        Self {
+            messages: vec![
+                "This lobby is powered by Ducky.\nType !help to see commands.".to_owned(),
+            ],
+            first_time: vec![],
+            returning: vec![],
+            roles: HashMap::new(),
+            delay_ms: 1500,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MotdConfigValue {
+    Message(String),
+    Section {
+        #[serde(default)]
+        messages: Option<Vec<String>>,
+        #[serde(default)]
+        first_time: Vec<String>,
+        #[serde(default)]
+        returning: Vec<String>,
+        #[serde(default)]
+        roles: HashMap<Role, Vec<String>>,
+        #[serde(default)]
+        delay_ms: Option<u64>,
+    },
+}
+
+impl From<MotdConfigValue> for MotdConfig {
+    fn from(value: MotdConfigValue) -> Self {
+        let default = MotdConfig::default();
+        match value {
+            MotdConfigValue::Message(message) => MotdConfig {
+                messages: vec![message],
+                ..default
+            },
+            MotdConfigValue::Section {
+                messages,
+                first_time,
+                returning,
+                roles,
+                delay_ms,
+            } => MotdConfig {
+                messages: messages.unwrap_or(default.messages),
+                first_time,
+                returning,
+                roles,
+                delay_ms: delay_ms.unwrap_or(default.delay_ms),
+            },
+        }
+    }
+}
+
+/// The `[join_leave]` section. Chat messages sent to the lobby when players join or leave. Messages
+/// are templates, with `{name}` set to the player's name.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct JoinLeaveConfig {
+    pub join: bool,
+    pub join_message: String,
+    /// Sent instead of the join message to players who have been in the lobby before.
+    pub welcome_back: bool,
+    pub welcome_back_message: String,
+    pub leave: bool,
+    pub leave_message: String,
+    pub kick: bool,
+    pub kick_message: String,
+    pub ban: bool,
+    pub ban_message: String,
+}
+
+impl Default for JoinLeaveConfig {
+    fn default() -> Self {
+        Self {
+            join: false,
+            join_message: "{name} joined the lobby.".to_owned(),
+            welcome_back: false,
+            welcome_back_message: "Welcome back, {name}!".to_owned(),
+            leave: false,
+            leave_message: "{name} left the lobby.".to_owned(),
+            kick: false,
+            kick_message: "{name} was kicked.".to_owned(),
+            ban: false,
+            ban_message: "{name} was banned.".to_owned(),
+        }
+    }
+}
+
+/// An `[[announcements]]` entry. A message which is broadcast to the lobby on a schedule.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AnnouncementConfig {
+    /// A message template, see `TemplateContext`.
+    pub message: String,
+    /// Messages to pick from at random instead of `message`. The same message is not picked twice
+    /// in a row.
+    pub pool: Vec<String>,
+    /// Broadcast every this many minutes. 0 disables the interval.
+    pub interval_minutes: u64,
+    /// UTC times of day to broadcast at, such as `"18:30"`.
+    pub times: Vec<String>,
+    /// Skip the announcement if fewer players than this are in the lobby, excluding the host.
+    pub min_players: usize,
+}
+
+/// The `[spawns]` section. Tunes the actors the host spawns on its own, such as fish and rain. The
+/// defaults match the game.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SpawnsConfig {
+    /// The most actors the host spawns on its own at once, across all types.
+    pub max_actors: usize,
+    /// How often a fish spawn, alien fish spawn, raincloud or void portal may spawn.
+    pub interval_secs: u64,
+    /// How often a flock of birds may spawn.
+    pub bird_interval_secs: u64,
+    /// How often a metal spawn may spawn.
+    pub metal_interval_secs: u64,
+    /// The chance of a fish spawn on each interval.
+    pub fish_chance: f64,
+    /// The chance of an alien fish spawn on each interval.
+    pub alien_chance: f64,
+    /// How many intervals must pass after an alien fish spawn before the next one.
+    pub alien_cooldown: u64,
+    /// The chance of a raincloud on each interval, once the rain buildup check passes.
+    pub rain_chance: f64,
+    /// The rain buildup starts at a random value up to this.
+    pub rain_buildup_start: f64,
+    /// How much the rain buildup grows on an interval without rain.
+    pub rain_buildup_step: f64,
+    /// The chance that the rain buildup grows on an interval without rain.
+    pub rain_buildup_chance: f64,
+    /// The chance of a void portal on each interval.
+    pub void_portal_chance: f64,
+    /// How fast rainclouds in `main_zone` drift across the map, in units per second. 0 keeps them
+    /// in place.
+    pub raincloud_speed: f64,
+    /// How long each actor type lives before despawning, such as `fish_spawn = 80`. 0 never
+    /// despawns. Types which are left out keep their default.
+    #[serde(deserialize_with = "deserialize_spawn_lifetimes")]
+    pub lifetime_secs: HashMap<ActorType, u64>,
+    /// The most actors of each type the host spawns on its own at once, such as `fish_spawn = 16`.
+    /// Types which are left out keep their default.
+    #[serde(deserialize_with = "deserialize_spawn_limits")]
+    pub limits: HashMap<ActorType, usize>,
+}
+
+impl SpawnsConfig {
+    /// Returns how long an actor of the given type lives, or `None` if it never despawns.
+    pub fn lifetime(&self, actor_type: &ActorType) -> Option<Duration> {
+        self.lifetime_secs
+            .get(actor_type)
+            .filter(|secs| **secs > 0)
+            .map(|secs| Duration::from_secs(*secs))
+    }
+
+    /// Returns the most actors of the given type the host spawns on its own at once.
+    pub fn limit(&self, actor_type: &ActorType) -> usize {
+        *self.limits.get(actor_type).unwrap_or(&usize::MAX)
+    }
+
+    /// Checks that every chance is between 0 and 1 and `rain_buildup_start` is above 0.
+    pub fn validate(&self) -> Result<(), String> {
+        let chances = [
+            ("fish_chance", self.fish_chance),
+            ("alien_chance", self.alien_chance),
+            ("rain_chance", self.rain_chance),
+            ("rain_buildup_chance", self.rain_buildup_chance),
+            ("void_portal_chance", self.void_portal_chance),
+        ];
+        for (key, chance) in chances {
+            if !(0.0..=1.0).contains(&chance) {
+                return Err(format!("{key} must be between 0 and 1, got {chance}"));
+            }
+        }
+        // Also rejects NaN.
+        if !(self.rain_buildup_start > 0.0 && self.rain_buildup_start.is_finite()) {
+            return Err(format!(
+                "rain_buildup_start must be above 0, got {}",
+                self.rain_buildup_start
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns this config with the values set by the given profile.
+    pub fn with_profile(&self, profile: &SpawnProfileConfig) -> SpawnsConfig {
+        let mut lifetime_secs = self.lifetime_secs.clone();
+        lifetime_secs.extend(profile.lifetime_secs.clone());
+        let mut limits = self.limits.clone();
+        limits.extend(profile.limits.clone());
+
+        SpawnsConfig {
+            max_actors: profile.max_actors.unwrap_or(self.max_actors),
+            interval_secs: profile.interval_secs.unwrap_or(self.interval_secs),
+            bird_interval_secs: profile
+                .bird_interval_secs
+                .unwrap_or(self.bird_interval_secs),
+            metal_interval_secs: profile
+                .metal_interval_secs
+                .unwrap_or(self.metal_interval_secs),
+            fish_chance: profile.fish_chance.unwrap_or(self.fish_chance),
+            alien_chance: profile.alien_chance.unwrap_or(self.alien_chance),
+            alien_cooldown: profile.alien_cooldown.unwrap_or(self.alien_cooldown),
+            rain_chance: profile.rain_chance.unwrap_or(self.rain_chance),
+            rain_buildup_start: self.rain_buildup_start,
+            rain_buildup_step: profile.rain_buildup_step.unwrap_or(self.rain_buildup_step),
+            rain_buildup_chance: profile
+                .rain_buildup_chance
+                .unwrap_or(self.rain_buildup_chance),
+            void_portal_chance: profile
+                .void_portal_chance
+                .unwrap_or(self.void_portal_chance),
+            raincloud_speed: profile.raincloud_speed.unwrap_or(self.raincloud_speed),
+            lifetime_secs,
+            limits,
+        }
+    }
+}
+
+impl Default for SpawnsConfig {
+    fn default() -> Self {
+        Self {
+            max_actors: 30, // 32 - 2 dedicated rain spawn slots.
+            interval_secs: 10,
+            bird_interval_secs: 10,
+            metal_interval_secs: 20,
+            fish_chance: 0.5,
+            // randf() < 0.01 and randf() < 0.4
+            alien_chance: 0.004,
+            alien_cooldown: 16,
+            rain_chance: 0.12,
+            rain_buildup_start: 0.2,
+            rain_buildup_step: 0.001,
+            rain_buildup_chance: 0.75,
+            // randf() < 0.01 and randf() < 0.25
+            void_portal_chance: 0.0025,
+            raincloud_speed: 0.5,
+            lifetime_secs: default_spawn_lifetimes(),
+            limits: default_spawn_limits(),
+        }
+    }
+}
+
+fn default_spawn_lifetimes() -> HashMap<ActorType, u64> {
+    actor_types()
+        .filter(|(_, info)| info.lifetime_secs > 0)
+        .map(|(actor_type, info)| (actor_type, info.lifetime_secs))
+        .collect()
+}
+
+fn default_spawn_limits() -> HashMap<ActorType, usize> {
+    HashMap::from([
+        (ActorType::Raincloud, 2),
+        (ActorType::FishSpawn, 16),
+        (ActorType::FishSpawnAlien, 4),
+        (ActorType::MetalSpawn, 8),
+        (ActorType::VoidPortal, 1),
+        (ActorType::AmbientBird, 9),
+    ])
+}
+
+fn deserialize_spawn_lifetimes<'de, D>(deserializer: D) -> Result<HashMap<ActorType, u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut lifetimes = default_spawn_lifetimes();
+    lifetimes.extend(HashMap::<ActorType, u64>::deserialize(deserializer)?);
+    Ok(lifetimes)
+}
+
+fn deserialize_spawn_limits<'de, D>(deserializer: D) -> Result<HashMap<ActorType, usize>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut limits = default_spawn_limits();
+    limits.extend(HashMap::<ActorType, usize>::deserialize(deserializer)?);
+    Ok(limits)
+}
+
+/// A `[spawn_profiles.<name>]` section. A set of `[spawns]` values which can be switched to for a
+/// while, such as during a community event. Values which are left out keep the `[spawns]` value.
+/// `rain_buildup_start` is left out since the rain buildup is only drawn once, on start.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SpawnProfileConfig {
+    /// Broadcast when the profile is switched to. A template, where `{duration}` is how long the
+    /// profile lasts. Empty sends nothing.
+    pub start_message: String,
+    /// Broadcast when the profile ends. A template. Empty sends nothing.
+    pub end_message: String,
+    pub max_actors: Option<usize>,
+    pub interval_secs: Option<u64>,
+    pub bird_interval_secs: Option<u64>,
+    pub metal_interval_secs: Option<u64>,
+    pub fish_chance: Option<f64>,
+    pub alien_chance: Option<f64>,
+    pub alien_cooldown: Option<u64>,
+    pub rain_chance: Option<f64>,
+    pub rain_buildup_step: Option<f64>,
+    pub rain_buildup_chance: Option<f64>,
+    pub void_portal_chance: Option<f64>,
+    pub raincloud_speed: Option<f64>,
+    pub lifetime_secs: HashMap<ActorType, u64>,
+    pub limits: HashMap<ActorType, usize>,
+}
+
+fn default_spawn_profiles() -> HashMap<String, SpawnProfileConfig> {
+    HashMap::from([
+        ("normal".to_owned(), SpawnProfileConfig::default()),
+        (
+            "fish_frenzy".to_owned(),
+            SpawnProfileConfig {
+                start_message: "A fish frenzy has started! It lasts {duration}.".to_owned(),
+                end_message: "The fish frenzy is over.".to_owned(),
+                interval_secs: Some(4),
+                fish_chance: Some(1.0),
+                lifetime_secs: HashMap::from([(ActorType::FishSpawn, 40)]),
+                limits: HashMap::from([(ActorType::FishSpawn, 24)]),
+                ..SpawnProfileConfig::default()
+            },
+        ),
+        (
+            "storm".to_owned(),
+            SpawnProfileConfig {
+                start_message: "A storm is rolling in! It lasts {duration}.".to_owned(),
+                end_message: "The storm has passed.".to_owned(),
+                rain_chance: Some(1.0),
+                rain_buildup_step: Some(0.1),
+                rain_buildup_chance: Some(1.0),
+                raincloud_speed: Some(1.5),
+                ..SpawnProfileConfig::default()
+            },
+        ),
+        (
+            "void_night".to_owned(),
+            SpawnProfileConfig {
+                start_message: "The void is opening... It lasts {duration}.".to_owned(),
+                end_message: "The void has closed.".to_owned(),
+                alien_chance: Some(0.05),
+                alien_cooldown: Some(4),
+                void_portal_chance: Some(0.05),
+                limits: HashMap::from([(ActorType::VoidPortal, 3)]),
+                ..SpawnProfileConfig::default()
+            },
+        ),
+    ])
+}
+
+fn deserialize_spawn_profiles<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<String, SpawnProfileConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut profiles = default_spawn_profiles();
+    let overrides = HashMap::<String, SpawnProfileConfig>::deserialize(deserializer)?;
+    profiles.extend(overrides);
+    Ok(profiles)
+}
+
+/// A `[[spawn_schedule]]` entry. Switches to a spawn profile at the given times.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SpawnScheduleConfig {
+    /// The name of a `[spawn_profiles.<name>]` section or a built-in profile.
+    pub profile: String,
+    /// UTC times of day to switch at, such as `"20:00"`.
+    pub times: Vec<String>,
+    pub duration_minutes: u64,
+}
+
+impl Default for SpawnScheduleConfig {
+    fn default() -> Self {
+        Self {
+            profile: String::new(),
+            times: vec![],
+            duration_minutes: 60,
+        }
+    }
+}
+
+/// The `[props]` section. Limits the actors players create, such as boomboxes and campfires.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PropsConfig {
+    /// The most props each player can have at once.
+    pub max_per_player: usize,
+    /// The most props of each type each player can have at once, such as `boombox = 1`. Types
+    /// which are left out are only limited by `max_per_player`.
+    pub limits: HashMap<ActorType, usize>,
+    /// The most props all players together can have at once. 0 means no cap.
+    pub max_total: usize,
+    /// Overrides for players with the given role, such as `[props.roles.trusted]`.
+    pub roles: HashMap<Role, PropsRoleConfig>,
+}
+
+impl PropsConfig {
+    /// Returns the most props a player with the given role can have at once.
+    pub fn max_per_player(&self, role: Role) -> usize {
+        self.roles
+            .get(&role)
+            .and_then(|overrides| overrides.max_per_player)
+            .unwrap_or(self.max_per_player)
+    }
+
+    /// Returns the most props of the given type a player with the given role can have at once, or
+    /// `None` if only `max_per_player` applies.
+    pub fn limit(&self, role: Role, actor_type: &ActorType) -> Option<usize> {
+        self.roles
+            .get(&role)
+            .and_then(|overrides| overrides.limits.get(actor_type))
+            .or(self.limits.get(actor_type))
+            .copied()
+    }
+}
+
+impl Default for PropsConfig {
+    fn default() -> Self {
+        PropsConfig {
+            max_per_player: 32,
+            limits: HashMap::new(),
+            max_total: 0,
+            roles: HashMap::new(),
+        }
+    }
+}
+
+/// A `[props.roles.<role>]` section. Values which are left out fall back to `[props]`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PropsRoleConfig {
+    pub max_per_player: Option<usize>,
+    pub limits: HashMap<ActorType, usize>,
+}
+
+/// The `[audit_log]` section.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct AuditLogConfig {
+    pub enabled: bool,
+    /// The directory which daily audit log files are written to.
+    pub directory: String,
+    /// The size at which a new file is started for the same day. 0 disables the cap.
+    pub max_file_bytes: u64,
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            directory: "logs".to_owned(),
+            max_file_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// The `[http_api]` section. Changes only take effect after a restart.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct HttpApiConfig {
+    pub enabled: bool,
+    /// The address and port to listen on. Only bind to a public address behind a TLS proxy.
+    pub bind: String,
+    /// Required in the `Authorization: Bearer <token>` header of every request. The API does not
+    /// start without a token.
+    pub token: String,
+}
+
+impl Default for HttpApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: "127.0.0.1:8033".to_owned(),
+            token: String::new(),
+        }
+    }
+}
+
+// The token is left out so it isn't printed with the rest of the config on start.
+impl fmt::Debug for HttpApiConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpApiConfig")
+            .field("enabled", &self.enabled)
+            .field("bind", &self.bind)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The `[logging]` section. Changes only take effect after a restart.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// A filter such as `info` or `info,game::spawn=debug`. The `RUST_LOG` environment variable
+    /// takes precedence.
+    pub level: String,
+    pub format: LogFormat,
+    /// A file to write logs to in addition to stdout. Empty disables file output.
+    pub file: String,
+    /// The size at which the log file is rotated. 0 disables rotation.
+    pub max_file_bytes: u64,
+    /// How many rotated files to keep.
+    pub max_files: u32,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_owned(),
+            format: LogFormat::Text,
+            file: String::new(),
+            max_file_bytes: 10 * 1024 * 1024,
+            max_files: 5,
         }
     }
 }
@@ -67,14 +841,11 @@ impl Default for Config {
 fn default_name() -> String {
     "A Ducky Server".to_string()
 }
-fn default_motd() -> String {
-    "This lobby is powered by Ducky.\nType !help to see commands.".to_string()
-}
 fn default_game_version() -> String {
     "1.12".to_string()
 }
 fn default_lobby_code() -> String {
-    lobby_code()
+    lobby_code(&mut rand::thread_rng())
 }
 fn default_max_players() -> u32 {
     12
@@ -91,3 +862,49 @@ fn default_tag_modded() -> bool {
 fn default_ban_list() -> Vec<u64> {
     vec![]
 }
+fn default_ban_file() -> String {
+    "bans.json".to_string()
+}
+fn default_admins() -> Vec<u64> {
+    vec![]
+}
+fn default_mute_file() -> String {
+    "mutes.json".to_string()
+}
+fn default_mute_announce() -> bool {
+    false
+}
+fn default_mute_escalation_offenses() -> u32 {
+    3
+}
+fn default_mute_escalation_tempban_minutes() -> u64 {
+    30
+}
+fn default_seen_file() -> String {
+    "seen.json".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpawnsConfig;
+
+    #[test]
+    fn spawns_validation() {
+        assert!(SpawnsConfig::default().validate().is_ok());
+
+        for rain_buildup_start in [0.0, -0.1, f64::NAN, f64::INFINITY] {
+            let spawns = SpawnsConfig {
+                rain_buildup_start,
+                ..SpawnsConfig::default()
+            };
+            assert!(spawns.validate().is_err());
+        }
+        for fish_chance in [-0.1, 1.1, f64::NAN] {
+            let spawns = SpawnsConfig {
+                fish_chance,
+                ..SpawnsConfig::default()
+            };
+            assert!(spawns.validate().is_err());
+        }
+    }
+}
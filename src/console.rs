@@ -0,0 +1,147 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead},
+    sync::mpsc::Sender,
+    thread,
+};
+
+use crate::{
+    command::{run_command, CommandContext, CommandSource},
+    game::Game,
+    server::Server,
+    time::format_duration,
+};
+
+static TAG: &str = "console";
+
+/// Reads lines from stdin on a separate thread and sends them to the game thread.
+pub fn init_console(sender_console_line: Sender<String>) {
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if sender_console_line.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Handles a line typed into the console. Lines starting with `!` run chat commands as the host,
+/// lines starting with `/` run console-only commands, and any other text is broadcast as server
+/// chat.
+pub fn on_console_line(server: &mut Server, game: &mut Game, line: String) {
+    let line = line.trim();
+    if line.is_empty() {
+        return;
+    }
+
+    if line.starts_with('!') {
+        let host_steam_id = server.host_steam_id;
+        let found = CommandContext::parse(host_steam_id, CommandSource::Console, line)
+            .map(|command_ctx| run_command(server, game, command_ctx))
+            .unwrap_or(false);
+        if !found {
+            println!("[{TAG}] Unknown command: {line}");
+        }
+    } else if let Some(command) = line.strip_prefix('/') {
+        run_console_command(server, game, command);
+    } else {
+        server.broadcast_chat_message(line);
+        println!("[{TAG}] Broadcast: {line}");
+    }
+}
+
+fn run_console_command(server: &mut Server, game: &mut Game, command: &str) {
+    match command.split_whitespace().next().unwrap_or("") {
+        "help" => println!(
+            "[{TAG}] Console commands: /players, /actors, /lobby, /reload, /shutdown. Chat \
+            commands start with !, e.g. !kick <player>. Any other text is broadcast as chat."
+        ),
+        "players" => print_players(server, game),
+        "actors" => print_actors(server, game),
+        "lobby" => print_lobby(server),
+        "reload" => {
+            let _ = crate::reload_config(server);
+        }
+        "shutdown" => {
+            println!("[{TAG}] Shutting down...");
+            server.shutdown_requested = true;
+        }
+        _ => println!("[{TAG}] Unknown console command: /{command}. Type /help for commands."),
+    }
+}
+
+fn print_players(server: &Server, game: &Game) {
+    println!("[{TAG}] {} players:", server.players.count());
+    for player in server.players.iter() {
+        let location = game
+            .actor_manager
+            .get_player_actor(&player.steam_id)
+            .map(|actor| {
+                format!(
+                    "zone = {}, position = ({:.1}, {:.1}, {:.1})",
+                    actor.zone, actor.position.x, actor.position.y, actor.position.z
+                )
+            })
+            .unwrap_or("no player actor".to_owned());
+        let ping = player
+            .ping
+            .map(|ping| format!("{}ms", ping.as_millis()))
+            .unwrap_or("?".to_owned());
+        println!(
+            "[{TAG}]   {} ({}, {:?}): {location}, session = {}, ping = {ping}",
+            player.name,
+            player.steam_id.raw(),
+            player.role,
+            format_duration(player.session_duration())
+        );
+    }
+}
+
+fn print_actors(server: &Server, game: &Game) {
+    let host_steam_id = server.host_steam_id;
+    // (host actors, user actors) by actor type
+    let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for actor in game.actor_manager.get_all_actors() {
+        let count = counts
+            .entry(String::from(actor.actor_type.clone()))
+            .or_default();
+        if actor.creator_id == host_steam_id {
+            count.0 += 1;
+        } else {
+            count.1 += 1;
+        }
+    }
+
+    let total: usize = counts.values().map(|(host, user)| host + user).sum();
+    println!("[{TAG}] {total} actors:");
+    for (actor_type, (host, user)) in counts {
+        println!(
+            "[{TAG}]   {actor_type}: {} (host {host}, users {user})",
+            host + user
+        );
+    }
+}
+
+fn print_lobby(server: &Server) {
+    let config = &server.config;
+    println!(
+        "[{TAG}] Lobby: lobby_id = {}, name = {}, code = {}, players = {}/{}, unlisted = {}",
+        server
+            .lobby_id
+            .map(|lobby_id| lobby_id.raw().to_string())
+            .unwrap_or("none".to_owned()),
+        config.name,
+        config.lobby_code,
+        server.players.count(),
+        config.max_players,
+        config.unlisted
+    );
+    println!(
+        "[{TAG}] Moderation: bans = {}, mutes = {}",
+        server.ban_list.count(),
+        server.mute_list.count()
+    );
+}
@@ -0,0 +1,220 @@
+use std::{
+    collections::HashSet,
+    sync::mpsc::{self, Receiver, Sender},
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    game::actor::Actor,
+    packet::variant::Vector3,
+    time::{format_rfc3339_utc, system_time_since_unix_epoch_seconds},
+};
+
+static TAG: &str = "event";
+/// The event types which `moderation` stands for in a subscriber's filter.
+static MODERATION_EVENT_TYPES: [&str; 7] = [
+    "kick",
+    "ban",
+    "unban",
+    "mute",
+    "unmute",
+    "filter",
+    "vote_kick",
+];
+
+/// Events which are streamed to subscribers but not written to the audit log.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GameEvent {
+    ActorSpawn {
+        actor_id: i64,
+        actor_type: String,
+        creator_id: u64,
+        zone: String,
+        position: Vector3,
+    },
+    ActorDespawn {
+        actor_id: i64,
+        actor_type: String,
+    },
+    /// A summary of the ticks in the last second.
+    Tick {
+        ticks: u32,
+        mean_ms: f64,
+        max_ms: f64,
+        /// Ticks which took longer than the tick interval.
+        overruns: u32,
+        users: usize,
+        actors: usize,
+    },
+}
+
+impl GameEvent {
+    pub fn actor_spawn(actor: &Actor) -> Self {
+        GameEvent::ActorSpawn {
+            actor_id: actor.id,
+            actor_type: String::from(actor.actor_type.clone()),
+            creator_id: actor.creator_id.raw(),
+            zone: actor.zone.clone(),
+            position: actor.position.clone(),
+        }
+    }
+}
+
+/// A consumer of the event stream, such as an open `/api/events` connection.
+pub struct EventSubscriber {
+    /// The event types to send. `None` sends every event.
+    types: Option<HashSet<String>>,
+    /// Receives the event type and the event as a JSON object.
+    sender: Sender<(String, String)>,
+}
+
+impl EventSubscriber {
+    pub fn new(types: Option<Vec<String>>, sender: Sender<(String, String)>) -> Self {
+        let types = types.map(|types| {
+            types
+                .into_iter()
+                .flat_map(|event_type| {
+                    if event_type == "moderation" {
+                        MODERATION_EVENT_TYPES.map(str::to_owned).to_vec()
+                    } else {
+                        vec![event_type]
+                    }
+                })
+                .collect()
+        });
+
+        Self { types, sender }
+    }
+
+    fn wants(&self, event_type: &str) -> bool {
+        self.types
+            .as_ref()
+            .map(|types| types.contains(event_type))
+            .unwrap_or(true)
+    }
+}
+
+/// Fans out server events to subscribers. Subscribers may be added from any thread through the
+/// sender returned by `subscriber_sender`, and are dropped once their receiving end is closed.
+pub struct EventBus {
+    subscribers: Vec<EventSubscriber>,
+    sender_subscriber: Sender<EventSubscriber>,
+    receiver_subscriber: Receiver<EventSubscriber>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender_subscriber, receiver_subscriber) = mpsc::channel();
+        Self {
+            subscribers: vec![],
+            sender_subscriber,
+            receiver_subscriber,
+        }
+    }
+
+    pub fn subscriber_sender(&self) -> Sender<EventSubscriber> {
+        self.sender_subscriber.clone()
+    }
+
+    /// Sends an event to all subscribers which want its type. The event must serialize to an
+    /// object with an `event` field holding its type, like `AuditEvent` and `GameEvent`.
+    pub fn publish<T: Serialize>(&mut self, event: &T) {
+        while let Ok(subscriber) = self.receiver_subscriber.try_recv() {
+            self.subscribers.push(subscriber);
+            info!(
+                target: TAG,
+                "Added event subscriber: subscribers = {}",
+                self.subscribers.len()
+            );
+        }
+        if self.subscribers.is_empty() {
+            return;
+        }
+
+        let mut value = match serde_json::to_value(event) {
+            Ok(value) => value,
+            Err(e) => {
+                warn!(target: TAG, "Failed serializing event: error = {e}");
+                return;
+            }
+        };
+        let event_type = value["event"].as_str().unwrap_or("unknown").to_owned();
+        if let Value::Object(fields) = &mut value {
+            fields.insert(
+                "timestamp".to_owned(),
+                Value::String(format_rfc3339_utc(system_time_since_unix_epoch_seconds())),
+            );
+        }
+        let json = value.to_string();
+
+        let subscriber_count = self.subscribers.len();
+        self.subscribers.retain(|subscriber| {
+            !subscriber.wants(&event_type)
+                || subscriber
+                    .sender
+                    .send((event_type.clone(), json.clone()))
+                    .is_ok()
+        });
+        if self.subscribers.len() < subscriber_count {
+            info!(
+                target: TAG,
+                "Removed closed event subscribers: subscribers = {}",
+                self.subscribers.len()
+            );
+        }
+    }
+}
+
+/// Collects tick durations and summarizes them once per second.
+pub struct TickMetrics {
+    tick_interval: Duration,
+    started_at: Instant,
+    ticks: u32,
+    total: Duration,
+    max: Duration,
+    overruns: u32,
+}
+
+impl TickMetrics {
+    pub fn new(tick_interval: Duration) -> Self {
+        Self {
+            tick_interval,
+            started_at: Instant::now(),
+            ticks: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+            overruns: 0,
+        }
+    }
+
+    /// Records how long a tick took. Returns a `tick` event once a second has passed since the
+    /// last one.
+    pub fn record(&mut self, duration: Duration, users: usize, actors: usize) -> Option<GameEvent> {
+        self.ticks += 1;
+        self.total += duration;
+        self.max = self.max.max(duration);
+        if duration > self.tick_interval {
+            self.overruns += 1;
+        }
+        if self.started_at.elapsed() < Duration::from_secs(1) {
+            return None;
+        }
+
+        let event = GameEvent::Tick {
+            ticks: self.ticks,
+            mean_ms: self.total.as_secs_f64() * 1000.0 / self.ticks as f64,
+            max_ms: self.max.as_secs_f64() * 1000.0,
+            overruns: self.overruns,
+            users,
+            actors,
+        };
+        *self = Self::new(self.tick_interval);
+
+        Some(event)
+    }
+}
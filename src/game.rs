@@ -1,34 +1,62 @@
 use actor::ActorManager;
+use clock::Clock;
+use log::info;
 use peer::PeerManager;
 use spawn::SpawnManager;
 
-use crate::server::Server;
+use crate::{
+    config::Config,
+    random::{game_rng, GameRng},
+    server::Server,
+};
 
 pub mod actor;
+pub mod clock;
 pub mod peer;
 pub mod spawn;
 
+static TAG: &str = "game";
+
 pub struct Game {
     pub actor_manager: ActorManager,
     pub spawn_manager: SpawnManager,
     pub peer_manager: PeerManager,
+    /// Every spawn decision and actor ID goes through this, see `GameRng`.
+    pub rng: GameRng,
+    pub clock: Clock,
 }
 
 impl Game {
-    pub fn new() -> Self {
+    pub fn new(config: &Config, clock: Clock) -> Self {
+        let (mut rng, seed) = game_rng(config.seed);
+        info!(target: TAG, "Seeded random number generator: seed = {seed}");
+
         Self {
-            actor_manager: ActorManager::new(),
-            spawn_manager: SpawnManager::new(),
-            peer_manager: PeerManager::new(),
+            actor_manager: ActorManager::new(clock.now()),
+            spawn_manager: SpawnManager::new(config, &mut rng, &clock),
+            peer_manager: PeerManager::new(clock.now()),
+            rng,
+            clock,
         }
     }
 
     pub fn on_ready(&mut self, server: &mut Server) {
-        self.spawn_manager.on_ready(server, &mut self.actor_manager);
+        self.spawn_manager.on_ready(
+            server,
+            &mut self.actor_manager,
+            &mut self.rng,
+            &self.clock,
+        );
     }
     
     pub fn on_update(&mut self, server: &mut Server) {
-        self.spawn_manager.on_update(server, &mut self.actor_manager);
-        self.peer_manager.on_update(server);
+        self.spawn_manager.on_update(
+            server,
+            &mut self.actor_manager,
+            &mut self.rng,
+            &self.clock,
+        );
+        self.actor_manager.on_update(server, self.clock.now());
+        self.peer_manager.on_update(server, self.clock.now());
     }
 }
@@ -1,8 +1,15 @@
-use std::{collections::HashMap, sync::mpsc::Sender};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
+};
 
+use log::{debug, warn};
+use serde::Deserialize;
 use steamworks::{SendType, SteamId};
 
 use crate::{
+    config::PropsConfig,
     packet::{
         util::{
             build_actor_action_packet, build_actor_update_packet, build_instance_actor_packet,
@@ -11,13 +18,21 @@ use crate::{
         variant::{Dictionary, VariantValue, Vector3},
         OutgoingP2pPacketRequest, P2pChannel, P2pPacketTarget,
     },
+    player::Role,
+    random::{godot_randi, GameRng},
     server::Server,
 };
 
+pub mod type_info;
+
 static TAG: &str = "game::actor";
-static MAX_ACTORS_PER_PLAYER: usize = 32;
+/// The most actors the host can have at once, as a safety net on top of the `[spawns]` limits.
+static MAX_HOST_ACTORS: usize = 32;
+/// How often every host actor is synced, changed or not, for peers which missed a packet.
+static REFRESH_INTERVAL: Duration = Duration::from_secs(30);
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+#[serde(try_from = "String")]
 pub enum ActorType {
     Unknown,
     Player,
@@ -49,19 +64,60 @@ pub enum ActorType {
     Beer,
     Greenscreen,
     PortableBait,
+    /// A type Ducky has no variant for, such as a modded prop or an item from a newer game
+    /// version. The type string is kept so it can be sent on as it was received.
+    Other(String),
 }
 
 impl ActorType {
     /// Returns if this ActorType can only be created by the host.
     pub fn is_create_by_host_only(&self) -> bool {
+        self.info().host_only
+    }
+
+    /// How often changes to host actors of this type are broadcast with `actor_update`.
+    pub fn sync_interval(&self) -> Duration {
         match self {
-            ActorType::FishSpawn => true,
-            ActorType::FishSpawnAlien => true,
-            ActorType::Raincloud => true,
-            ActorType::MetalSpawn => true,
-            ActorType::AmbientBird => true,
-            ActorType::VoidPortal => true,
-            _ => false,
+            ActorType::AmbientBird => Duration::from_millis(250),
+            ActorType::Raincloud => Duration::from_millis(500),
+            _ => Duration::from_secs(1),
+        }
+    }
+
+    /// Returns the type string used in packets.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ActorType::Unknown => "unknown",
+            ActorType::Player => "player",
+            ActorType::FishSpawn => "fish_spawn",
+            ActorType::FishSpawnAlien => "fish_spawn_alien",
+            ActorType::Raincloud => "raincloud",
+            ActorType::RaincloudTiny => "raincloud_tiny",
+            ActorType::AquaFish => "aqua_fish",
+            ActorType::MetalSpawn => "metal_spawn",
+            ActorType::AmbientBird => "ambient_bird",
+            ActorType::VoidPortal => "void_portal",
+            ActorType::Picnic => "picnic",
+            ActorType::Canvas => "canvas",
+            ActorType::Bush => "bush",
+            ActorType::Rock => "rock",
+            ActorType::FishTrap => "fish_trap",
+            ActorType::FishTrapOcean => "fish_trap_ocean",
+            ActorType::IslandTiny => "island_tiny",
+            ActorType::IslandMed => "island_med",
+            ActorType::IslandBig => "island_big",
+            ActorType::Boombox => "boombox",
+            ActorType::Well => "well",
+            ActorType::Campfire => "campfire",
+            ActorType::Chair => "chair",
+            ActorType::Table => "table",
+            ActorType::TherapistChair => "therapist_chair",
+            ActorType::Toilet => "toilet",
+            ActorType::Whoopie => "whoopie",
+            ActorType::Beer => "beer",
+            ActorType::Greenscreen => "greenscreen",
+            ActorType::PortableBait => "portable_bait",
+            ActorType::Other(name) => name,
         }
     }
 }
@@ -98,51 +154,68 @@ impl From<&str> for ActorType {
             "beer" => ActorType::Beer,
             "greenscreen" => ActorType::Greenscreen,
             "portable_bait" => ActorType::PortableBait,
-            _ => ActorType::Unknown,
+            "" | "unknown" => ActorType::Unknown,
+            other => ActorType::Other(other.to_owned()),
         }
     }
 }
 
-impl From<ActorType> for &'static str {
+impl From<ActorType> for String {
     fn from(value: ActorType) -> Self {
-        match value {
-            ActorType::Unknown => "unknown",
-            ActorType::Player => "player",
-            ActorType::FishSpawn => "fish_spawn",
-            ActorType::FishSpawnAlien => "fish_spawn_alien",
-            ActorType::Raincloud => "raincloud",
-            ActorType::RaincloudTiny => "raincloud_tiny",
-            ActorType::AquaFish => "aqua_fish",
-            ActorType::MetalSpawn => "metal_spawn",
-            ActorType::AmbientBird => "ambient_bird",
-            ActorType::VoidPortal => "void_portal",
-            ActorType::Picnic => "picnic",
-            ActorType::Canvas => "canvas",
-            ActorType::Bush => "bush",
-            ActorType::Rock => "rock",
-            ActorType::FishTrap => "fish_trap",
-            ActorType::FishTrapOcean => "fish_trap_ocean",
-            ActorType::IslandTiny => "island_tiny",
-            ActorType::IslandMed => "island_med",
-            ActorType::IslandBig => "island_big",
-            ActorType::Boombox => "boombox",
-            ActorType::Well => "well",
-            ActorType::Campfire => "campfire",
-            ActorType::Chair => "chair",
-            ActorType::Table => "table",
-            ActorType::TherapistChair => "therapist_chair",
-            ActorType::Toilet => "toilet",
-            ActorType::Whoopie => "whoopie",
-            ActorType::Beer => "beer",
-            ActorType::Greenscreen => "greenscreen",
-            ActorType::PortableBait => "portable_bait",
+        value.as_str().to_owned()
+    }
+}
+
+/// Used for config, where a name which is not in the type table is most likely a typo.
+impl TryFrom<String> for ActorType {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let actor_type = ActorType::from(value.as_str());
+        if actor_type.is_known() {
+            Ok(actor_type)
+        } else {
+            Err(format!("unknown actor type: {value}"))
         }
     }
 }
 
-impl From<ActorType> for String {
-    fn from(value: ActorType) -> Self {
-        <&str>::from(value).to_string()
+/// Why a user cannot create an actor.
+#[derive(Debug)]
+pub enum CreateActorError {
+    /// The user already has a `player` actor.
+    DuplicatePlayer,
+    /// Only the host can create actors of this type.
+    HostOnly,
+    /// The user has the most actors of this type their role allows.
+    TypeLimit(ActorType, usize),
+    /// The user has the most props their role allows.
+    PlayerLimit(usize),
+    /// The lobby has the most props `[props] max_total` allows.
+    LobbyLimit(usize),
+}
+
+impl CreateActorError {
+    /// Returns a chat message which explains the limit to the player. `None` for errors which an
+    /// unmodified game client does not run into.
+    pub fn chat_message(&self) -> Option<String> {
+        let reason = match self {
+            CreateActorError::DuplicatePlayer | CreateActorError::HostOnly => return None,
+            CreateActorError::TypeLimit(actor_type, limit) => {
+                format!(
+                    "You reached the limit of {limit} for {}",
+                    actor_type.as_str()
+                )
+            }
+            CreateActorError::PlayerLimit(limit) => {
+                format!("You reached the limit of {limit} props")
+            }
+            CreateActorError::LobbyLimit(limit) => {
+                format!("The lobby reached the limit of {limit} props")
+            }
+        };
+
+        Some(format!("{reason}, so the server ignores your new one."))
     }
 }
 
@@ -204,21 +277,90 @@ pub struct ActorManager {
     actors_by_id: HashMap<i64, Actor>,
     actor_ids_by_creator: HashMap<SteamId, Vec<i64>>,
     player_actor_ids_by_creator: HashMap<SteamId, i64>,
+    /// Host actors which changed since they were last synced.
+    dirty_actor_ids: HashSet<i64>,
+    /// When dirty host actors of each type are next synced.
+    next_sync_at: HashMap<ActorType, Instant>,
+    next_refresh_at: Instant,
 }
 
 impl ActorManager {
-    pub fn new() -> Self {
+    pub fn new(now: Instant) -> Self {
         Self {
             actors_by_id: HashMap::new(),
             actor_ids_by_creator: HashMap::new(),
             player_actor_ids_by_creator: HashMap::new(),
+            dirty_actor_ids: HashSet::new(),
+            next_sync_at: HashMap::new(),
+            next_refresh_at: now + REFRESH_INTERVAL,
+        }
+    }
+
+    /// Broadcasts `actor_update` for dirty host actors whose type is due, see
+    /// `ActorType::sync_interval`. Every `REFRESH_INTERVAL`, all host actors are marked dirty.
+    pub fn on_update(&mut self, server: &Server, now: Instant) {
+        let host_steam_id = server.host_steam_id;
+        if now >= self.next_refresh_at {
+            self.next_refresh_at = now + REFRESH_INTERVAL;
+            for problem in self.check_consistency() {
+                warn!(target: TAG, "Actor index inconsistency: {problem}");
+            }
+            let host_actor_ids = self
+                .actor_ids_by_creator
+                .get(&host_steam_id)
+                .cloned()
+                .unwrap_or_default();
+            for id in host_actor_ids {
+                self.mark_dirty(&id);
+            }
+        }
+        if self.dirty_actor_ids.is_empty() {
+            return;
+        }
+
+        let mut synced_types = HashSet::new();
+        let dirty_actor_ids: Vec<i64> = self.dirty_actor_ids.iter().copied().collect();
+        for id in dirty_actor_ids {
+            let Some(actor) = self.actors_by_id.get(&id) else {
+                self.dirty_actor_ids.remove(&id);
+                continue;
+            };
+            let is_due = self
+                .next_sync_at
+                .get(&actor.actor_type)
+                .is_none_or(|next_sync_at| now >= *next_sync_at);
+            if !is_due {
+                continue;
+            }
+
+            send_variant_p2p(
+                &server.sender_p2p_packet,
+                build_actor_update_packet(actor),
+                P2pPacketTarget::All,
+                P2pChannel::ActorUpdate,
+                SendType::Reliable,
+            );
+            synced_types.insert(actor.actor_type.clone());
+            self.dirty_actor_ids.remove(&id);
+        }
+        for actor_type in synced_types {
+            let next_sync_at = now + actor_type.sync_interval();
+            self.next_sync_at.insert(actor_type, next_sync_at);
+        }
+    }
+
+    /// Marks a host actor as changed, so it is synced to peers on the next interval for its type.
+    pub fn mark_dirty(&mut self, id: &i64) {
+        if self.actors_by_id.contains_key(id) {
+            self.dirty_actor_ids.insert(*id);
         }
     }
 
     pub fn sync_all_actors(&self, server: &Server, target: P2pPacketTarget) {
-        let actors = self.get_actors_by_creator(&server.steam_client.user().steam_id());
-        println!(
-            "[{TAG}] Sending actor_update for {} actors...",
+        let actors = self.get_actors_by_creator(&server.host_steam_id);
+        debug!(
+            target: TAG,
+            "Sending actor_update for {} actors...",
             actors.len()
         );
         for actor in actors {
@@ -251,8 +393,93 @@ impl ActorManager {
         }
     }
 
-    /// Inserts an actor into the ActorManager. This does not perform any checks or network sync.
-    pub fn insert_actor(&mut self, actor: Actor) {
+    /// Checks that `actors_by_id`, `actor_ids_by_creator` and `player_actor_ids_by_creator` agree
+    /// with each other. Returns a description of every problem found.
+    pub fn check_consistency(&self) -> Vec<String> {
+        let mut problems = vec![];
+        for (id, actor) in &self.actors_by_id {
+            if actor.id != *id {
+                problems.push(format!("actor {} is stored under ID {id}", actor.id));
+            }
+            let listed = self
+                .actor_ids_by_creator
+                .get(&actor.creator_id)
+                .map(|ids| ids.iter().filter(|x| *x == id).count())
+                .unwrap_or(0);
+            if listed != 1 {
+                problems.push(format!(
+                    "actor {id} is listed {listed} times for its creator {}",
+                    actor.creator_id.raw()
+                ));
+            }
+            if actor.actor_type == ActorType::Player
+                && self.player_actor_ids_by_creator.get(&actor.creator_id) != Some(id)
+            {
+                problems.push(format!(
+                    "player actor {id} is not the player actor of its creator {}",
+                    actor.creator_id.raw()
+                ));
+            }
+        }
+        for (creator_id, ids) in &self.actor_ids_by_creator {
+            for id in ids {
+                match self.actors_by_id.get(id) {
+                    Some(actor) if actor.creator_id == *creator_id => {}
+                    Some(actor) => problems.push(format!(
+                        "actor {id} is listed for creator {} but was created by {}",
+                        creator_id.raw(),
+                        actor.creator_id.raw()
+                    )),
+                    None => problems.push(format!(
+                        "actor {id} is listed for creator {} but does not exist",
+                        creator_id.raw()
+                    )),
+                }
+            }
+        }
+        for (creator_id, id) in &self.player_actor_ids_by_creator {
+            let is_player = self.actors_by_id.get(id).is_some_and(|actor| {
+                actor.actor_type == ActorType::Player && actor.creator_id == *creator_id
+            });
+            if !is_player {
+                problems.push(format!(
+                    "player actor {id} of creator {} is missing or not a player actor",
+                    creator_id.raw()
+                ));
+            }
+        }
+
+        problems
+    }
+
+    /// Returns a random actor ID which no actor uses yet, like the IDs the game picks with
+    /// `randi()`.
+    pub fn allocate_id(&self, rng: &mut GameRng) -> i64 {
+        loop {
+            let id = godot_randi(rng);
+            if !self.actors_by_id.contains_key(&id) {
+                return id;
+            }
+            debug!(target: TAG, "Skipping actor ID which is in use: id = {id}");
+        }
+    }
+
+    /// Inserts an actor into the ActorManager. This does not perform any checks or network sync,
+    /// except that an actor whose ID is already in use is rejected. Returns if it was inserted.
+    pub fn insert_actor(&mut self, actor: Actor) -> bool {
+        if let Some(existing) = self.actors_by_id.get(&actor.id) {
+            if existing.creator_id == actor.creator_id {
+                debug!(target: TAG, "Ignoring actor which already exists: actor = {actor:?}");
+            } else {
+                warn!(
+                    target: TAG,
+                    "Rejected actor with an ID in use by another creator: actor = {actor:?}, \
+                     existing = {existing:?}"
+                );
+            }
+            return false;
+        }
+
         self.actors_by_id.insert(actor.id.clone(), actor.clone());
         self.actor_ids_by_creator
             .entry(actor.creator_id.clone())
@@ -262,10 +489,13 @@ impl ActorManager {
             self.player_actor_ids_by_creator
                 .insert(actor.creator_id.clone(), actor.id.clone());
         }
+
+        true
     }
 
     /// Removes an actor from the ActorManager. This does not perform any checks or network sync.
     pub fn remove_actor(&mut self, id: &i64) {
+        self.dirty_actor_ids.remove(id);
         if let Some(actor) = self.actors_by_id.remove(id) {
             if let Some(actor_ids) = self.actor_ids_by_creator.get_mut(&actor.creator_id) {
                 actor_ids.retain(|x| x != id);
@@ -286,11 +516,13 @@ impl ActorManager {
         creator_id: &SteamId,
         actor: Actor,
     ) -> bool {
-        if !self.user_can_create_actor(creator_id, true, &actor.actor_type) {
+        if !self.host_can_create_actor(creator_id, &actor.actor_type) {
             return false;
         }
 
-        self.insert_actor(actor.clone());
+        if !self.insert_actor(actor.clone()) {
+            return false;
+        }
         send_variant_p2p(
             &sender_p2p_packet,
             build_instance_actor_packet(&actor),
@@ -386,6 +618,16 @@ impl ActorManager {
             .unwrap_or(vec![])
     }
 
+    /// Returns the number of actors.
+    pub fn count_actors(&self) -> usize {
+        self.actors_by_id.len()
+    }
+
+    /// Gets all actors.
+    pub fn get_all_actors(&self) -> Vec<&Actor> {
+        self.actors_by_id.values().collect()
+    }
+
     /// Gets all actors of the given actor type.
     pub fn get_actors_by_type(&self, actor_type: &ActorType) -> Vec<&Actor> {
         self.actors_by_id
@@ -394,21 +636,206 @@ impl ActorManager {
             .collect()
     }
 
-    /// Checks if a user can create an actor of the given type.
+    /// Checks if the host can create another actor.
+    pub fn host_can_create_actor(&self, host_id: &SteamId, actor_type: &ActorType) -> bool {
+        !actor_type.info().counts_toward_limit
+            || count_limited_actors(self.get_actors_by_creator(host_id)) < MAX_HOST_ACTORS
+    }
+
+    /// Checks if a user with the given role can create an actor of the given type, following the
+    /// `[props]` limits.
     pub fn user_can_create_actor(
         &self,
         creator_id: &SteamId,
-        creator_is_host: bool,
+        creator_role: Role,
         actor_type: &ActorType,
-    ) -> bool {
+        config: &PropsConfig,
+    ) -> Result<(), CreateActorError> {
         if *actor_type == ActorType::Player {
-            return self.player_actor_ids_by_creator.get(creator_id).is_none();
+            return match self.player_actor_ids_by_creator.get(creator_id) {
+                Some(_) => Err(CreateActorError::DuplicatePlayer),
+                None => Ok(()),
+            };
         }
 
-        if actor_type.is_create_by_host_only() && !creator_is_host {
-            return false;
+        if actor_type.is_create_by_host_only() && creator_role != Role::Host {
+            return Err(CreateActorError::HostOnly);
+        }
+
+        if !actor_type.info().counts_toward_limit {
+            return Ok(());
+        }
+
+        let creator_actors = self.get_actors_by_creator(creator_id);
+        if let Some(limit) = config.limit(creator_role, actor_type) {
+            let count = creator_actors
+                .iter()
+                .filter(|actor| actor.actor_type == *actor_type)
+                .count();
+            if count >= limit {
+                return Err(CreateActorError::TypeLimit(actor_type.clone(), limit));
+            }
+        }
+
+        let limit = config.max_per_player(creator_role);
+        if count_limited_actors(creator_actors) >= limit {
+            return Err(CreateActorError::PlayerLimit(limit));
+        }
+
+        if config.max_total > 0 {
+            let props = self
+                .actors_by_id
+                .values()
+                .filter(|actor| !actor.actor_type.is_create_by_host_only());
+            if count_limited_actors(props) >= config.max_total {
+                return Err(CreateActorError::LobbyLimit(config.max_total));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts the given actors which count toward the actor limits.
+fn count_limited_actors<'a>(actors: impl IntoIterator<Item = &'a Actor>) -> usize {
+    actors
+        .into_iter()
+        .filter(|actor| actor.actor_type.info().counts_toward_limit)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time::Instant};
+
+    use steamworks::SteamId;
+
+    use super::{Actor, ActorManager, ActorType, CreateActorError};
+    use crate::{
+        config::{PropsConfig, PropsRoleConfig},
+        packet::variant::Vector3,
+        player::Role,
+        random::{game_rng, godot_randi},
+    };
+
+    fn actor(id: i64, creator_id: u64, actor_type: ActorType) -> Actor {
+        Actor {
+            id,
+            creator_id: SteamId::from_raw(creator_id),
+            actor_type,
+            zone: "main_zone".to_owned(),
+            zone_owner: -1,
+            position: Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            rotation: Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
         }
+    }
+
+    #[test]
+    fn allocate_id_skips_ids_in_use() {
+        let (mut rng, _) = game_rng(Some(7));
+        let mut expected = rng.clone();
+        let taken = godot_randi(&mut expected);
+        let next = godot_randi(&mut expected);
+        let mut actor_manager = ActorManager::new(Instant::now());
+        actor_manager.insert_actor(actor(taken, 1, ActorType::Boombox));
+
+        assert_eq!(actor_manager.allocate_id(&mut rng), next);
+    }
+
+    #[test]
+    fn insert_actor_rejects_ids_in_use() {
+        let mut actor_manager = ActorManager::new(Instant::now());
+        assert!(actor_manager.insert_actor(actor(5, 1, ActorType::Boombox)));
+        assert!(!actor_manager.insert_actor(actor(5, 1, ActorType::Campfire)));
+        assert!(!actor_manager.insert_actor(actor(5, 2, ActorType::Campfire)));
+
+        let existing = actor_manager.get_actor(&5).unwrap();
+        assert_eq!(existing.creator_id, SteamId::from_raw(1));
+        assert_eq!(existing.actor_type, ActorType::Boombox);
+        assert!(actor_manager
+            .get_actors_by_creator(&SteamId::from_raw(2))
+            .is_empty());
+        assert!(actor_manager.check_consistency().is_empty());
+    }
+
+    #[test]
+    fn check_consistency_finds_broken_indexes() {
+        let mut actor_manager = ActorManager::new(Instant::now());
+        actor_manager.insert_actor(actor(1, 1, ActorType::Player));
+        actor_manager.insert_actor(actor(2, 1, ActorType::Boombox));
+        actor_manager.remove_actor(&2);
+        assert!(actor_manager.check_consistency().is_empty());
+
+        actor_manager
+            .actor_ids_by_creator
+            .get_mut(&SteamId::from_raw(1))
+            .unwrap()
+            .push(3);
+        actor_manager
+            .player_actor_ids_by_creator
+            .insert(SteamId::from_raw(2), 1);
+
+        assert_eq!(actor_manager.check_consistency().len(), 2);
+    }
+
+    #[test]
+    fn user_can_create_actor_follows_props_limits() {
+        let player = SteamId::from_raw(1);
+        let config = PropsConfig {
+            max_per_player: 3,
+            limits: HashMap::from([(ActorType::Boombox, 1)]),
+            max_total: 5,
+            roles: HashMap::from([(
+                Role::Trusted,
+                PropsRoleConfig {
+                    max_per_player: Some(4),
+                    limits: HashMap::from([(ActorType::Boombox, 2)]),
+                },
+            )]),
+        };
+        let mut actor_manager = ActorManager::new(Instant::now());
+        actor_manager.insert_actor(actor(1, 1, ActorType::Player));
+        actor_manager.insert_actor(actor(2, 1, ActorType::Boombox));
+        actor_manager.insert_actor(actor(3, 1, ActorType::Campfire));
+        let can_create = |actor_manager: &ActorManager, role, actor_type| {
+            actor_manager.user_can_create_actor(&player, role, &actor_type, &config)
+        };
 
-        self.get_actors_by_creator(creator_id).len() < MAX_ACTORS_PER_PLAYER
+        assert!(matches!(
+            can_create(&actor_manager, Role::Player, ActorType::Player),
+            Err(CreateActorError::DuplicatePlayer)
+        ));
+        assert!(matches!(
+            can_create(&actor_manager, Role::Player, ActorType::FishSpawn),
+            Err(CreateActorError::HostOnly)
+        ));
+        assert!(matches!(
+            can_create(&actor_manager, Role::Player, ActorType::Boombox),
+            Err(CreateActorError::TypeLimit(ActorType::Boombox, 1))
+        ));
+        assert!(can_create(&actor_manager, Role::Trusted, ActorType::Boombox).is_ok());
+        assert!(can_create(&actor_manager, Role::Player, ActorType::Chair).is_ok());
+
+        actor_manager.insert_actor(actor(4, 1, ActorType::Chair));
+        assert!(matches!(
+            can_create(&actor_manager, Role::Player, ActorType::Chair),
+            Err(CreateActorError::PlayerLimit(3))
+        ));
+        assert!(can_create(&actor_manager, Role::Trusted, ActorType::Chair).is_ok());
+
+        actor_manager.insert_actor(actor(5, 2, ActorType::Chair));
+        actor_manager.insert_actor(actor(6, 2, ActorType::Chair));
+        assert!(matches!(
+            can_create(&actor_manager, Role::Trusted, ActorType::Chair),
+            Err(CreateActorError::LobbyLimit(5))
+        ));
     }
 }
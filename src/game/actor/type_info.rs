@@ -0,0 +1,84 @@
+use std::{collections::HashMap, fs, io, sync::LazyLock};
+
+use log::{info, warn};
+use serde::Deserialize;
+
+use super::ActorType;
+
+static TAG: &str = "game::actor::type_info";
+static ACTOR_TYPES_PATH: &str = "./data/actor_types.json";
+/// The table shipped with Ducky, used if `ACTOR_TYPES_PATH` cannot be read so that host-only
+/// types stay host-only.
+static BUILT_IN_ACTOR_TYPES: &str = include_str!("../../../data/actor_types.json");
+/// Used for types which are not in the table.
+static DEFAULT_TYPE_INFO: ActorTypeInfo = ActorTypeInfo {
+    host_only: false,
+    counts_toward_limit: true,
+    lifetime_secs: 0,
+};
+
+/// Metadata for every actor type, keyed by the type string used in packets.
+static ACTOR_TYPES: LazyLock<HashMap<String, ActorTypeInfo>> = LazyLock::new(load_actor_types);
+
+/// Metadata of an actor type from `data/actor_types.json`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ActorTypeInfo {
+    /// Only the host can create actors of this type.
+    pub host_only: bool,
+    /// Actors of this type count toward the per-player actor limit.
+    pub counts_toward_limit: bool,
+    /// The default for `[spawns.lifetime_secs]`. 0 means never despawn.
+    pub lifetime_secs: u64,
+}
+
+impl Default for ActorTypeInfo {
+    fn default() -> Self {
+        DEFAULT_TYPE_INFO.clone()
+    }
+}
+
+impl ActorType {
+    /// Returns the metadata for this type. Types which are not in the table get the defaults.
+    pub fn info(&self) -> &'static ActorTypeInfo {
+        ACTOR_TYPES.get(self.as_str()).unwrap_or(&DEFAULT_TYPE_INFO)
+    }
+
+    /// Returns if this type is in the table.
+    pub fn is_known(&self) -> bool {
+        ACTOR_TYPES.contains_key(self.as_str())
+    }
+}
+
+/// Returns every type in the table with its metadata.
+pub fn actor_types() -> impl Iterator<Item = (ActorType, &'static ActorTypeInfo)> {
+    ACTOR_TYPES
+        .iter()
+        .map(|(name, info)| (ActorType::from(name.as_str()), info))
+}
+
+fn load_actor_types() -> HashMap<String, ActorTypeInfo> {
+    match read_actor_types(ACTOR_TYPES_PATH) {
+        Ok(actor_types) => {
+            info!(
+                target: TAG,
+                "Loaded {} actor types: path = {ACTOR_TYPES_PATH}",
+                actor_types.len()
+            );
+            actor_types
+        }
+        Err(e) => {
+            warn!(
+                target: TAG,
+                "Failed reading actor types, using built-in types: path = {ACTOR_TYPES_PATH}, \
+                 error = {e}"
+            );
+            serde_json::from_str(BUILT_IN_ACTOR_TYPES).unwrap_or_default()
+        }
+    }
+}
+
+fn read_actor_types(path: &str) -> io::Result<HashMap<String, ActorTypeInfo>> {
+    serde_json::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
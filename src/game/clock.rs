@@ -0,0 +1,51 @@
+use std::time::{Duration, Instant};
+
+use crate::time::system_time_since_unix_epoch_seconds;
+
+/// Where the game gets the current time from. Simulations and tests use a manual clock, which only
+/// moves when it is advanced.
+pub enum Clock {
+    Real,
+    Manual {
+        start: Instant,
+        start_unix_secs: u64,
+        elapsed: Duration,
+    },
+}
+
+impl Clock {
+    /// Creates a manual clock starting at the current time.
+    pub fn manual() -> Self {
+        Clock::Manual {
+            start: Instant::now(),
+            start_unix_secs: system_time_since_unix_epoch_seconds(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        match self {
+            Clock::Real => Instant::now(),
+            Clock::Manual { start, elapsed, .. } => *start + *elapsed,
+        }
+    }
+
+    /// Returns the current time as a Unix timestamp in seconds.
+    pub fn unix_secs(&self) -> u64 {
+        match self {
+            Clock::Real => system_time_since_unix_epoch_seconds(),
+            Clock::Manual {
+                start_unix_secs,
+                elapsed,
+                ..
+            } => start_unix_secs + elapsed.as_secs(),
+        }
+    }
+
+    /// Moves a manual clock forward. The real clock can't be moved.
+    pub fn advance(&mut self, duration: Duration) {
+        if let Clock::Manual { elapsed, .. } = self {
+            *elapsed += duration;
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::{
     time::{Duration, Instant},
 };
 
+use log::debug;
 use steamworks::{SendType, SteamId};
 
 use crate::{
@@ -22,10 +23,10 @@ pub struct PeerManager {
 }
 
 impl PeerManager {
-    pub fn new() -> Self {
+    pub fn new(now: Instant) -> Self {
         Self {
             steam_ids_need_actor_update: HashSet::new(),
-            last_actor_update_request: Instant::now(),
+            last_actor_update_request: now,
         }
     }
 
@@ -33,28 +34,29 @@ impl PeerManager {
         self.steam_ids_need_actor_update.insert(steam_id);
     }
 
-    pub fn on_update(&mut self, server: &Server) {
-        if self.last_actor_update_request.elapsed() > TIMEOUT {
-            self.request_actor_update(server);
+    pub fn on_update(&mut self, server: &Server, now: Instant) {
+        if now.duration_since(self.last_actor_update_request) > TIMEOUT {
+            self.request_actor_update(server, now);
         }
     }
 
-    fn request_actor_update(&mut self, server: &Server) {
+    fn request_actor_update(&mut self, server: &Server, now: Instant) {
         let steam_ids_to_update = std::mem::take(&mut self.steam_ids_need_actor_update);
         for steam_id in steam_ids_to_update {
-            println!(
-                "[{TAG}] Requesting actor update: steam_id = {}",
+            debug!(
+                target: TAG,
+                "Requesting actor update: steam_id = {}",
                 steam_id.raw()
             );
             send_variant_p2p(
                 &server.sender_p2p_packet,
-                build_actor_request_packet(server.steam_client.user().steam_id()),
+                build_actor_request_packet(server.host_steam_id),
                 P2pPacketTarget::SteamId(steam_id),
                 P2pChannel::GameState,
                 SendType::Reliable,
             );
         }
 
-        self.last_actor_update_request = Instant::now();
+        self.last_actor_update_request = now;
     }
 }
@@ -1,54 +1,47 @@
 use std::{
     collections::HashMap,
     fs,
-    sync::{mpsc::Sender, LazyLock},
     time::{Duration, Instant},
 };
 
+use bird::FleeingBird;
+use log::{debug, info, warn};
 use rand::seq::SliceRandom;
 
 use crate::{
-    packet::{variant::Vector3, OutgoingP2pPacketRequest},
-    random::{godot_rand_range, godot_randf, godot_randi},
+    config::{Config, SpawnsConfig},
+    event::GameEvent,
+    packet::variant::Vector3,
+    random::{godot_rand_range, godot_randf, godot_randi, GameRng},
     server::Server,
+    template::TemplateContext,
+    time::{format_duration, parse_time_of_day},
 };
 
-use super::actor::{Actor, ActorManager, ActorType};
+use super::{
+    actor::{Actor, ActorManager, ActorType},
+    clock::Clock,
+};
+
+mod bird;
+mod raincloud;
 
 static TAG: &str = "game::spawn";
-// TODO: make these configurable
-static SPAWN_LIFETIMES: LazyLock<HashMap<ActorType, Duration>> = LazyLock::new(|| {
-    let mut map = HashMap::new();
-    // One physics tick is 1/60s. We use Duration instead of network ticks since network is
-    // decoupled from physics ticks, but we only have a network loop.
-    map.insert(ActorType::Raincloud, Duration::from_secs(32500 / 60));
-    map.insert(ActorType::FishSpawn, Duration::from_secs(4800 / 60));
-    map.insert(ActorType::FishSpawnAlien, Duration::from_secs(14400 / 60));
-    map.insert(ActorType::MetalSpawn, Duration::from_secs(10000 / 60));
-    map.insert(ActorType::VoidPortal, Duration::from_secs(36000 / 60));
-    map.insert(ActorType::AmbientBird, Duration::MAX);
-
-    map
-});
-// TODO: make these configurable
-static SPAWN_COUNT_LIMITS: LazyLock<HashMap<ActorType, usize>> = LazyLock::new(|| {
-    let mut map = HashMap::new();
-    map.insert(ActorType::Raincloud, 2);
-    map.insert(ActorType::FishSpawn, 16);
-    map.insert(ActorType::FishSpawnAlien, 4);
-    map.insert(ActorType::MetalSpawn, 8);
-    map.insert(ActorType::VoidPortal, 1);
-    map.insert(ActorType::AmbientBird, 9);
-
-    map
-});
-static MAX_ACTORS: usize = 30; // 32 - 2 dedicated rain spawn slots.
+
+/// A spawn profile which replaces `[spawns]` for a limited time.
+pub struct SpawnEvent {
+    pub profile: String,
+    pub ends_at: Instant,
+}
 
 pub struct SpawnManager {
     /// Spawns which are built into the game.
     game_spawns: HashMap<ActorType, Vec<i64>>,
     /// Spawns initiated by user commands (these are still spawned by the host SteamId).
     user_spawns: HashMap<ActorType, Vec<i64>>,
+    /// Spawns requested by admins, such as through the HTTP API. These don't count towards the
+    /// limits for users.
+    admin_spawns: HashMap<ActorType, Vec<i64>>,
     /// A map of a spawn's actor ID to the Instant they should despawn.
     spawn_timeouts: HashMap<i64, Instant>,
     spawn_points: HashMap<String, Vec<Vector3>>,
@@ -57,71 +50,258 @@ pub struct SpawnManager {
     next_metal_spawn: Instant,
     alien_cooldown: u64,
     rain_chance: f64,
+    event: Option<SpawnEvent>,
+    /// The minute since the Unix epoch when `[[spawn_schedule]]` was last checked, so each time
+    /// only starts an event once.
+    last_schedule_minute: Option<u64>,
+    /// The time of the current tick, from the game's `Clock`.
+    now: Instant,
+    /// The drift direction of each raincloud, as an angle in radians around the y axis.
+    raincloud_directions: HashMap<i64, f64>,
+    fleeing_birds: HashMap<i64, FleeingBird>,
 }
 
-// TODO: make these configurable
-fn next_host_spawn() -> Instant {
-    Instant::now() + Duration::from_secs(10)
+fn next_host_spawn(now: Instant, config: &SpawnsConfig) -> Instant {
+    now + Duration::from_secs(config.interval_secs)
 }
 
-fn next_ambient_spawn() -> Instant {
-    Instant::now() + Duration::from_secs(10)
+fn next_ambient_spawn(now: Instant, config: &SpawnsConfig) -> Instant {
+    now + Duration::from_secs(config.bird_interval_secs)
 }
 
-fn next_metal_spawn() -> Instant {
-    Instant::now() + Duration::from_secs(20)
+fn next_metal_spawn(now: Instant, config: &SpawnsConfig) -> Instant {
+    now + Duration::from_secs(config.metal_interval_secs)
 }
 
 impl SpawnManager {
-    pub fn new() -> Self {
+    pub fn new(config: &Config, rng: &mut GameRng, clock: &Clock) -> Self {
         let spawn_points: HashMap<String, Vec<Vector3>> =
             match fs::read_to_string("./data/spawn_points.json") {
                 Ok(spawn_points) => serde_json::from_str(&spawn_points).unwrap_or(HashMap::new()),
                 _ => HashMap::new(),
             };
+        for schedule in &config.spawn_schedule {
+            for time in &schedule.times {
+                if parse_time_of_day(time).is_none() {
+                    warn!(target: TAG, "Ignoring invalid spawn schedule time: time = {time}");
+                }
+            }
+        }
+        let config = &config.spawns;
+        let now = clock.now();
 
         SpawnManager {
             game_spawns: HashMap::new(),
             user_spawns: HashMap::new(),
+            admin_spawns: HashMap::new(),
             spawn_timeouts: HashMap::new(),
             spawn_points,
-            next_host_spawn: next_host_spawn(),
-            next_ambient_spawn: next_ambient_spawn(),
-            next_metal_spawn: next_metal_spawn(),
-            alien_cooldown: 16, // default
-            rain_chance: godot_rand_range(0.0, 0.2),
+            next_host_spawn: next_host_spawn(now, config),
+            next_ambient_spawn: next_ambient_spawn(now, config),
+            next_metal_spawn: next_metal_spawn(now, config),
+            alien_cooldown: config.alien_cooldown,
+            rain_chance: godot_rand_range(rng, 0.0, config.rain_buildup_start),
+            event: None,
+            last_schedule_minute: None,
+            now,
+            raincloud_directions: HashMap::new(),
+            fleeing_birds: HashMap::new(),
         }
     }
 
-    pub fn on_ready(&mut self, server: &mut Server, actor_manager: &mut ActorManager) {
+    pub fn on_ready(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
+        clock: &Clock,
+    ) {
+        self.now = clock.now();
+        let config = self.spawns_config(&server.config);
         for _ in 0..4 {
-            self.spawn_game_metal_spawn(server, actor_manager);
+            self.spawn_game_metal_spawn(server, actor_manager, rng, &config);
         }
     }
 
-    pub fn on_update(&mut self, server: &mut Server, actor_manager: &mut ActorManager) {
-        if Instant::now() >= self.next_host_spawn {
-            self.next_host_spawn = next_host_spawn();
-            self.spawn_random_game_actor(server, actor_manager);
+    pub fn on_update(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
+        clock: &Clock,
+    ) {
+        let now = clock.now();
+        let elapsed = now.saturating_duration_since(self.now);
+        self.now = now;
+        if self.event.as_ref().is_some_and(|event| now >= event.ends_at) {
+            self.end_event(server);
         }
-        if Instant::now() >= self.next_ambient_spawn {
-            self.next_ambient_spawn = next_ambient_spawn();
-            self.spawn_game_bird(server, actor_manager);
+        self.start_scheduled_event(server, clock.unix_secs() / 60);
+
+        if now >= self.next_host_spawn
+            || now >= self.next_ambient_spawn
+            || now >= self.next_metal_spawn
+        {
+            let config = self.spawns_config(&server.config);
+            if now >= self.next_host_spawn {
+                self.next_host_spawn = next_host_spawn(now, &config);
+                self.spawn_random_game_actor(server, actor_manager, rng, &config);
+            }
+            if now >= self.next_ambient_spawn {
+                self.next_ambient_spawn = next_ambient_spawn(now, &config);
+                self.spawn_game_bird(server, actor_manager, rng, &config);
+            }
+            if now >= self.next_metal_spawn {
+                self.next_metal_spawn = next_metal_spawn(now, &config);
+                self.spawn_game_metal_spawn(server, actor_manager, rng, &config);
+            }
         }
-        if Instant::now() >= self.next_metal_spawn {
-            self.next_metal_spawn = next_metal_spawn();
-            self.spawn_game_metal_spawn(server, actor_manager);
+
+        if !actor_manager
+            .get_actors_by_type(&ActorType::Raincloud)
+            .is_empty()
+        {
+            let config = self.spawns_config(&server.config);
+            self.drift_rainclouds(server, actor_manager, &config, elapsed);
         }
+        self.update_birds(server, actor_manager, rng, elapsed);
 
         // Process expired actors.
-        for actor_id in self.get_actors_need_despawn(Instant::now()) {
-            println!("[{TAG}] Despawning expired actor: actor_id = {}", actor_id);
-            self.despawn_actor(&server.sender_p2p_packet, actor_manager, &actor_id);
+        for actor_id in self.get_actors_need_despawn(now) {
+            debug!(target: TAG, "Despawning expired actor: actor_id = {}", actor_id);
+            self.despawn_actor(server, actor_manager, &actor_id);
+        }
+    }
+
+    /// Returns `[spawns]` with the profile of the active event applied.
+    fn spawns_config(&self, config: &Config) -> SpawnsConfig {
+        match self
+            .event
+            .as_ref()
+            .and_then(|event| config.spawn_profiles.get(&event.profile))
+        {
+            Some(profile) => config.spawns.with_profile(profile),
+            None => config.spawns.clone(),
+        }
+    }
+
+    pub fn event(&self) -> Option<&SpawnEvent> {
+        self.event.as_ref()
+    }
+
+    /// Switches to a spawn profile for the given duration and announces it. An event which is
+    /// already active is ended first.
+    pub fn start_event(
+        &mut self,
+        server: &mut Server,
+        profile: &str,
+        duration: Duration,
+    ) -> Result<(), String> {
+        if !server.config.spawn_profiles.contains_key(profile) {
+            return Err(format!("Unknown spawn profile: {profile}"));
+        }
+        let Some(ends_at) = self.now.checked_add(duration) else {
+            return Err("That duration is too long.".to_owned());
+        };
+        self.end_event(server);
+
+        info!(
+            target: TAG,
+            "Starting spawn event: profile = {profile}, duration = {}",
+            format_duration(duration)
+        );
+        self.event = Some(SpawnEvent {
+            profile: profile.to_owned(),
+            ends_at,
+        });
+        let message = TemplateContext::new(server)
+            .with_duration(duration)
+            .render(&server.config.spawn_profiles[profile].start_message);
+        if !message.is_empty() {
+            server.broadcast_chat_message(&message);
+        }
+
+        Ok(())
+    }
+
+    /// Ends the active event, if any, and announces it. Spawns go back to `[spawns]`.
+    pub fn end_event(&mut self, server: &mut Server) -> bool {
+        let Some(event) = self.event.take() else {
+            return false;
+        };
+
+        info!(target: TAG, "Ending spawn event: profile = {}", event.profile);
+        let message = match server.config.spawn_profiles.get(&event.profile) {
+            Some(profile) => TemplateContext::new(server).render(&profile.end_message),
+            None => String::new(),
+        };
+        if !message.is_empty() {
+            server.broadcast_chat_message(&message);
+        }
+
+        true
+    }
+
+    /// Starts the `[[spawn_schedule]]` entry which is due at the given minute since the Unix epoch,
+    /// if any. Scheduled events do not replace an active event.
+    fn start_scheduled_event(&mut self, server: &mut Server, unix_minute: u64) {
+        if self.last_schedule_minute == Some(unix_minute) {
+            return;
+        }
+        self.last_schedule_minute = Some(unix_minute);
+
+        let minute_of_day = (unix_minute % (24 * 60)) as u32;
+        let Some(schedule) = server
+            .config
+            .spawn_schedule
+            .iter()
+            .find(|schedule| {
+                schedule
+                    .times
+                    .iter()
+                    .any(|time| parse_time_of_day(time) == Some(minute_of_day))
+            })
+            .cloned()
+        else {
+            return;
+        };
+        if let Some(event) = &self.event {
+            info!(
+                target: TAG,
+                "Skipping scheduled spawn event while another is active: profile = {}, active = {}",
+                schedule.profile,
+                event.profile
+            );
+            return;
+        }
+
+        let Some(duration) = schedule
+            .duration_minutes
+            .checked_mul(60)
+            .map(Duration::from_secs)
+        else {
+            warn!(
+                target: TAG,
+                "Skipping scheduled spawn event with a duration which is too long: profile = {}, \
+                 duration_minutes = {}",
+                schedule.profile,
+                schedule.duration_minutes
+            );
+            return;
+        };
+        if let Err(e) = self.start_event(server, &schedule.profile, duration) {
+            warn!(target: TAG, "Failed starting scheduled spawn event: error = {e}");
         }
     }
 
-    fn spawn_random_game_actor(&mut self, server: &mut Server, actor_manager: &mut ActorManager) {
-        let mut actor_type = if godot_randi() % 2 == 0 {
+    fn spawn_random_game_actor(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
+        config: &SpawnsConfig,
+    ) {
+        let mut actor_type = if godot_randf(rng) < config.fish_chance {
             ActorType::FishSpawn
         } else {
             ActorType::Unknown
@@ -130,8 +310,7 @@ impl SpawnManager {
         if let Some(alien_cooldown) = self.alien_cooldown.checked_sub(1) {
             self.alien_cooldown = alien_cooldown;
         }
-        // randf() < 0.01 and randf() < 0.4
-        if godot_randf() < 0.004
+        if godot_randf(rng) < config.alien_chance
             && actor_manager
                 .get_actors_by_type(&ActorType::FishSpawnAlien)
                 .len()
@@ -139,38 +318,36 @@ impl SpawnManager {
             && self.alien_cooldown <= 0
         {
             actor_type = ActorType::FishSpawnAlien;
-            self.alien_cooldown = 16;
+            self.alien_cooldown = config.alien_cooldown;
         }
 
-        if godot_randf() < self.rain_chance && godot_randf() < 0.12 {
+        if godot_randf(rng) < self.rain_chance && godot_randf(rng) < config.rain_chance {
             actor_type = ActorType::Raincloud;
             self.rain_chance = 0.0;
-        } else if godot_randf() < 0.75 {
-            self.rain_chance += 0.001;
+        } else if godot_randf(rng) < config.rain_buildup_chance {
+            self.rain_chance += config.rain_buildup_step;
         }
 
-        // randf() < 0.01 and randf() < 0.25
-        if godot_randf() < 0.0025 {
+        if godot_randf(rng) < config.void_portal_chance {
             actor_type = ActorType::VoidPortal;
         }
 
         match actor_type {
-            ActorType::FishSpawn => self.spawn_game_fish(server, actor_manager),
-            ActorType::FishSpawnAlien => self.spawn_game_fish_alien(server, actor_manager),
-            ActorType::Raincloud => self.spawn_game_raincloud(server, actor_manager),
-            ActorType::VoidPortal => self.spawn_game_void_portal(server, actor_manager),
+            ActorType::FishSpawn => self.spawn_game_fish(server, actor_manager, rng, config),
+            ActorType::FishSpawnAlien => {
+                self.spawn_game_fish_alien(server, actor_manager, rng, config)
+            }
+            ActorType::Raincloud => self.spawn_game_raincloud(server, actor_manager, rng, config),
+            ActorType::VoidPortal => {
+                self.spawn_game_void_portal(server, actor_manager, rng, config)
+            }
             _ => (),
         };
     }
 
     /// Despawns an actor and broadcasts the despawn packet to all clients.
-    fn despawn_actor(
-        &mut self,
-        sender: &Sender<OutgoingP2pPacketRequest>,
-        actor_manager: &mut ActorManager,
-        id: &i64,
-    ) {
-        println!("[{TAG}] Despawning actor: actor_id = {id}");
+    fn despawn_actor(&mut self, server: &mut Server, actor_manager: &mut ActorManager, id: &i64) {
+        debug!(target: TAG, "Despawning actor: actor_id = {id}");
 
         self.game_spawns.iter_mut().for_each(|(_, spawns)| {
             spawns.retain(|spawn_id| spawn_id != id);
@@ -178,8 +355,23 @@ impl SpawnManager {
         self.user_spawns.iter_mut().for_each(|(_, spawns)| {
             spawns.retain(|spawn_id| spawn_id != id);
         });
+        self.admin_spawns.iter_mut().for_each(|(_, spawns)| {
+            spawns.retain(|spawn_id| spawn_id != id);
+        });
         self.spawn_timeouts.remove(id);
-        actor_manager.despawn_host_actor(sender, id);
+        self.raincloud_directions.remove(id);
+        self.fleeing_birds.remove(id);
+        let actor_type = actor_manager
+            .get_actor(id)
+            .map(|actor| String::from(actor.actor_type.clone()));
+        if actor_manager.despawn_host_actor(&server.sender_p2p_packet, id) {
+            let actor_type = actor_type.unwrap_or_default();
+            server.metrics.record_despawn(&actor_type);
+            server.events.publish(&GameEvent::ActorDespawn {
+                actor_id: *id,
+                actor_type,
+            });
+        }
     }
 
     /// Spawns an actor triggered by the game logic. This will broadcast both `instance_actor` and
@@ -189,49 +381,62 @@ impl SpawnManager {
         context: &mut Server,
         actor_manager: &mut ActorManager,
         actor: Actor,
+        config: &SpawnsConfig,
     ) {
-        println!(
-            "[{TAG}] Spawning game actor with type: actor_type = {:?}",
+        debug!(
+            target: TAG,
+            "Spawning game actor with type: actor_type = {:?}",
             actor.actor_type
         );
         let id = actor.id.clone();
         let actor_type = actor.actor_type.clone();
+        let event = GameEvent::actor_spawn(&actor);
+        let lifetime = config.lifetime(&actor_type);
 
-        actor_manager.spawn_host_actor(
+        if !actor_manager.spawn_host_actor(
             &context.sender_p2p_packet,
-            &context.steam_client.user().steam_id(),
+            &context.host_steam_id,
             actor,
-        );
+        ) {
+            return;
+        }
+        context.metrics.record_spawn(actor_type.as_str());
+        context.events.publish(&event);
         self.game_spawns
             .entry(actor_type.clone())
             .or_insert(vec![])
             .push(id);
 
-        let spawn_lifetime = *SPAWN_LIFETIMES.get(&actor_type).unwrap_or(&Duration::MAX);
-        if spawn_lifetime < Duration::MAX {
-            Instant::now()
+        if let Some(spawn_lifetime) = lifetime {
+            self.now
                 .checked_add(spawn_lifetime)
                 .map(|i| self.spawn_timeouts.insert(id, i));
         }
     }
 
     /// Spawns a raincloud using game logic. This will broadcast the spawn to all clients.
-    fn spawn_game_raincloud(&mut self, server: &mut Server, actor_manager: &mut ActorManager) {
-        if !self.can_spawn_game_actor(&ActorType::Raincloud) {
-            println!("[{TAG}] Failed spawn_game_raincloud: actor count limit reached");
+    fn spawn_game_raincloud(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
+        config: &SpawnsConfig,
+    ) {
+        if !self.can_spawn_game_actor(config, &ActorType::Raincloud) {
+            debug!(target: TAG, "Failed spawn_game_raincloud: actor count limit reached");
             return;
         }
 
         let raincloud = Actor {
-            id: godot_randi(),
-            creator_id: server.steam_client.user().steam_id(),
+            id: actor_manager.allocate_id(rng),
+            creator_id: server.host_steam_id,
             actor_type: ActorType::Raincloud,
             zone: "main_zone".to_owned(),
             zone_owner: -1,
             position: Vector3 {
-                x: godot_rand_range(-100.0, 150.0),
+                x: godot_rand_range(rng, -100.0, 150.0),
                 y: 42.0,
-                z: godot_rand_range(-150.0, 100.0),
+                z: godot_rand_range(rng, -150.0, 100.0),
             },
             rotation: Vector3 {
                 x: 0.0,
@@ -240,33 +445,39 @@ impl SpawnManager {
             },
         };
 
-        self.spawn_game_actor(server, actor_manager, raincloud);
+        self.spawn_game_actor(server, actor_manager, raincloud, config);
     }
 
-    fn spawn_game_metal_spawn(&mut self, server: &mut Server, actor_manager: &mut ActorManager) {
-        if !self.can_spawn_game_actor(&ActorType::MetalSpawn) {
-            println!("[{TAG}] Failed spawn_game_metal_spawn: actor count limit reached");
+    fn spawn_game_metal_spawn(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
+        config: &SpawnsConfig,
+    ) {
+        if !self.can_spawn_game_actor(config, &ActorType::MetalSpawn) {
+            debug!(target: TAG, "Failed spawn_game_metal_spawn: actor count limit reached");
             return;
         }
 
-        let use_shoreline = godot_randf() < 0.15;
+        let use_shoreline = godot_randf(rng) < 0.15;
         let position = if use_shoreline {
-            self.random_spawn_point("shoreline_point")
+            self.random_spawn_point(rng, "shoreline_point")
         } else {
-            self.random_spawn_point("trash_point")
+            self.random_spawn_point(rng, "trash_point")
         };
         let Some(position) = position else {
-            println!("[{TAG}] Failed spawn_game_metal_spawn: no spawn point found");
+            warn!(target: TAG, "Failed spawn_game_metal_spawn: no spawn point found");
             return;
         };
 
         let mut position = position.clone();
-        position.x += godot_rand_range(-0.5, 0.5);
-        position.z += godot_rand_range(-0.5, 0.5);
+        position.x += godot_rand_range(rng, -0.5, 0.5);
+        position.z += godot_rand_range(rng, -0.5, 0.5);
 
         let metal_spawn = Actor {
-            id: godot_randi(),
-            creator_id: server.steam_client.user().steam_id(),
+            id: actor_manager.allocate_id(rng),
+            creator_id: server.host_steam_id,
             actor_type: ActorType::MetalSpawn,
             zone: "main_zone".to_owned(),
             zone_owner: -1,
@@ -278,24 +489,30 @@ impl SpawnManager {
             },
         };
 
-        self.spawn_game_actor(server, actor_manager, metal_spawn);
+        self.spawn_game_actor(server, actor_manager, metal_spawn, config);
     }
 
-    fn spawn_game_fish(&mut self, server: &mut Server, actor_manager: &mut ActorManager) {
-        if !self.can_spawn_game_actor(&ActorType::FishSpawn) {
-            println!("[{TAG}] Failed spawn_game_fish: actor count limit reached");
+    fn spawn_game_fish(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
+        config: &SpawnsConfig,
+    ) {
+        if !self.can_spawn_game_actor(config, &ActorType::FishSpawn) {
+            debug!(target: TAG, "Failed spawn_game_fish: actor count limit reached");
             return;
         }
 
-        let position = self.random_spawn_point("fish_spawn");
+        let position = self.random_spawn_point(rng, "fish_spawn");
         let Some(position) = position else {
-            println!("[{TAG}] Failed spawn_game_fish: no spawn point found");
+            warn!(target: TAG, "Failed spawn_game_fish: no spawn point found");
             return;
         };
 
         let fish_spawn = Actor {
-            id: godot_randi(),
-            creator_id: server.steam_client.user().steam_id(),
+            id: actor_manager.allocate_id(rng),
+            creator_id: server.host_steam_id,
             actor_type: ActorType::FishSpawn,
             zone: "main_zone".to_owned(),
             zone_owner: -1,
@@ -307,24 +524,30 @@ impl SpawnManager {
             },
         };
 
-        self.spawn_game_actor(server, actor_manager, fish_spawn);
+        self.spawn_game_actor(server, actor_manager, fish_spawn, config);
     }
 
-    fn spawn_game_fish_alien(&mut self, server: &mut Server, actor_manager: &mut ActorManager) {
-        if !self.can_spawn_game_actor(&ActorType::FishSpawnAlien) {
-            println!("[{TAG}] Failed spawn_game_fish_alien: actor count limit reached");
+    fn spawn_game_fish_alien(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
+        config: &SpawnsConfig,
+    ) {
+        if !self.can_spawn_game_actor(config, &ActorType::FishSpawnAlien) {
+            debug!(target: TAG, "Failed spawn_game_fish_alien: actor count limit reached");
             return;
         }
 
-        let position = self.random_spawn_point("fish_spawn");
+        let position = self.random_spawn_point(rng, "fish_spawn");
         let Some(position) = position else {
-            println!("[{TAG}] Failed spawn_game_fish_alien: no spawn point found");
+            warn!(target: TAG, "Failed spawn_game_fish_alien: no spawn point found");
             return;
         };
 
         let fish_spawn_alien = Actor {
-            id: godot_randi(),
-            creator_id: server.steam_client.user().steam_id(),
+            id: actor_manager.allocate_id(rng),
+            creator_id: server.host_steam_id,
             actor_type: ActorType::FishSpawnAlien,
             zone: "main_zone".to_owned(),
             zone_owner: -1,
@@ -336,28 +559,34 @@ impl SpawnManager {
             },
         };
 
-        self.spawn_game_actor(server, actor_manager, fish_spawn_alien);
+        self.spawn_game_actor(server, actor_manager, fish_spawn_alien, config);
     }
 
-    fn spawn_game_void_portal(&mut self, server: &mut Server, actor_manager: &mut ActorManager) {
-        if !self.can_spawn_game_actor(&ActorType::VoidPortal) {
-            println!("[{TAG}] Failed spawn_game_void_portal: actor count limit reached");
+    fn spawn_game_void_portal(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
+        config: &SpawnsConfig,
+    ) {
+        if !self.can_spawn_game_actor(config, &ActorType::VoidPortal) {
+            debug!(target: TAG, "Failed spawn_game_void_portal: actor count limit reached");
             return;
         }
 
-        let position = self.random_spawn_point("hidden_spot");
+        let position = self.random_spawn_point(rng, "hidden_spot");
         let Some(position) = position else {
-            println!("[{TAG}] Failed spawn_game_void_portal: no spawn point found");
+            warn!(target: TAG, "Failed spawn_game_void_portal: no spawn point found");
             return;
         };
 
         let mut position = position.clone();
-        position.x += godot_rand_range(-0.5, 0.5);
-        position.z += godot_rand_range(-0.5, 0.5);
+        position.x += godot_rand_range(rng, -0.5, 0.5);
+        position.z += godot_rand_range(rng, -0.5, 0.5);
 
         let void_portal = Actor {
-            id: godot_randi(),
-            creator_id: server.steam_client.user().steam_id(),
+            id: actor_manager.allocate_id(rng),
+            creator_id: server.host_steam_id,
             actor_type: ActorType::VoidPortal,
             zone: "main_zone".to_owned(),
             zone_owner: -1,
@@ -369,29 +598,35 @@ impl SpawnManager {
             },
         };
 
-        self.spawn_game_actor(server, actor_manager, void_portal);
+        self.spawn_game_actor(server, actor_manager, void_portal, config);
     }
 
-    fn spawn_game_bird(&mut self, server: &mut Server, actor_manager: &mut ActorManager) {
-        if !self.can_spawn_game_actor(&ActorType::AmbientBird) {
-            println!("[{TAG}] Failed spawn_game_bird: actor count limit reached");
+    fn spawn_game_bird(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
+        config: &SpawnsConfig,
+    ) {
+        if !self.can_spawn_game_actor(config, &ActorType::AmbientBird) {
+            debug!(target: TAG, "Failed spawn_game_bird: actor count limit reached");
             return;
         }
 
-        for _ in 0..(godot_randi() % 3 + 1) {
-            let position = self.random_spawn_point("trash_point");
+        for _ in 0..(godot_randi(rng) % 3 + 1) {
+            let position = self.random_spawn_point(rng, "trash_point");
             let Some(position) = position else {
-                println!("[{TAG}] Failed spawn_game_bird: no spawn point found");
+                warn!(target: TAG, "Failed spawn_game_bird: no spawn point found");
                 return;
             };
 
             let mut position = position.clone();
-            position.x += godot_rand_range(-2.5, 2.5);
-            position.z += godot_rand_range(-2.5, 2.5);
+            position.x += godot_rand_range(rng, -2.5, 2.5);
+            position.z += godot_rand_range(rng, -2.5, 2.5);
 
             let bird = Actor {
-                id: godot_randi(),
-                creator_id: server.steam_client.user().steam_id(),
+                id: actor_manager.allocate_id(rng),
+                creator_id: server.host_steam_id,
                 actor_type: ActorType::AmbientBird,
                 zone: "main_zone".to_owned(),
                 zone_owner: -1,
@@ -403,22 +638,23 @@ impl SpawnManager {
                 },
             };
 
-            self.spawn_game_actor(server, actor_manager, bird);
+            self.spawn_game_actor(server, actor_manager, bird, config);
         }
     }
 
     /// Returns if the current game state permits spawning the given actor type.
-    fn can_spawn_game_actor(&self, actor_type: &ActorType) -> bool {
-        self.game_spawns.values().map(|v| v.len()).sum::<usize>() < MAX_ACTORS
+    fn can_spawn_game_actor(&self, config: &SpawnsConfig, actor_type: &ActorType) -> bool {
+        self.game_spawns.values().map(|v| v.len()).sum::<usize>() < config.max_actors
             && self
                 .game_spawns
                 .get(actor_type)
                 .map(|v| v.len())
                 .unwrap_or(0)
-                < *SPAWN_COUNT_LIMITS.get(actor_type).unwrap_or(&usize::MAX)
+                < config.limit(actor_type)
     }
 
-    /// Returns a list of actor IDs that need to be despawned.
+    /// Returns a list of actor IDs that need to be despawned, sorted so the order doesn't depend on
+    /// the `HashMap` iteration order.
     pub fn get_actors_need_despawn(&self, now: Instant) -> Vec<i64> {
         let mut actors_need_despawn = vec![];
         for (id, spawn_timeout) in &self.spawn_timeouts {
@@ -426,6 +662,7 @@ impl SpawnManager {
                 actors_need_despawn.push(*id);
             }
         }
+        actors_need_despawn.sort_unstable();
 
         actors_need_despawn
     }
@@ -435,31 +672,94 @@ impl SpawnManager {
         server: &mut Server,
         actor_manager: &mut ActorManager,
         actor: Actor,
-    ) {
-        println!(
-            "[{TAG}] Spawning user actor with type: actor_type = {:?}",
+    ) -> bool {
+        debug!(
+            target: TAG,
+            "Spawning user actor with type: actor_type = {:?}",
             actor.actor_type
         );
-
-        let id = actor.id.clone();
+        let id = actor.id;
         let actor_type = actor.actor_type.clone();
-
-        actor_manager.spawn_host_actor(
-            &server.sender_p2p_packet,
-            &server.steam_client.user().steam_id(),
-            actor,
-        );
+        if !self.spawn_requested_actor(server, actor_manager, actor) {
+            return false;
+        }
         self.user_spawns
-            .entry(actor_type.clone())
+            .entry(actor_type)
             .or_insert(vec![])
             .push(id);
 
-        let spawn_lifetime = *SPAWN_LIFETIMES.get(&actor_type).unwrap_or(&Duration::MAX);
-        if spawn_lifetime < Duration::MAX {
-            Instant::now()
+        true
+    }
+
+    /// Spawns an actor requested by a user or an admin and schedules its despawn. The caller
+    /// tracks the ID. Returns if the actor was spawned.
+    fn spawn_requested_actor(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        actor: Actor,
+    ) -> bool {
+        let id = actor.id.clone();
+        let actor_type = actor.actor_type.clone();
+        let event = GameEvent::actor_spawn(&actor);
+        let lifetime = self.spawns_config(&server.config).lifetime(&actor_type);
+
+        if !actor_manager.spawn_host_actor(&server.sender_p2p_packet, &server.host_steam_id, actor)
+        {
+            return false;
+        }
+        server.metrics.record_spawn(actor_type.as_str());
+        server.events.publish(&event);
+
+        if let Some(spawn_lifetime) = lifetime {
+            self.now
                 .checked_add(spawn_lifetime)
                 .map(|i| self.spawn_timeouts.insert(id, i));
         }
+
+        true
+    }
+
+    /// Spawns an actor requested by an admin, such as through the HTTP API. The actor despawns
+    /// after the usual lifetime for its type. Returns the actor ID, or `None` if the host has too
+    /// many actors.
+    pub fn spawn_admin_actor(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
+        actor_type: ActorType,
+        zone: &str,
+        position: &Vector3,
+    ) -> Option<i64> {
+        let actor = Actor {
+            id: actor_manager.allocate_id(rng),
+            creator_id: server.host_steam_id,
+            actor_type: actor_type.clone(),
+            zone: zone.to_owned(),
+            zone_owner: -1,
+            position: position.clone(),
+            rotation: Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+        };
+        let id = actor.id;
+        debug!(
+            target: TAG,
+            "Spawning admin actor with type: actor_type = {:?}",
+            actor.actor_type
+        );
+        if !self.spawn_requested_actor(server, actor_manager, actor) {
+            return None;
+        }
+        self.admin_spawns
+            .entry(actor_type)
+            .or_insert(vec![])
+            .push(id);
+
+        Some(id)
     }
 
     /// Spawns a user triggered raincloud. This will broadcast the spawn to all clients.
@@ -467,12 +767,13 @@ impl SpawnManager {
         &mut self,
         server: &mut Server,
         actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
         zone: &str,
         position: &Vector3,
     ) {
         let raincloud = Actor {
-            id: godot_randi(),
-            creator_id: server.steam_client.user().steam_id(),
+            id: actor_manager.allocate_id(rng),
+            creator_id: server.host_steam_id,
             actor_type: ActorType::Raincloud,
             zone: zone.to_owned(),
             zone_owner: -1,
@@ -487,18 +788,11 @@ impl SpawnManager {
         self.spawn_user_actor(server, actor_manager, raincloud);
     }
 
-    pub fn can_spawn_user_actor(&self, actor_type: &ActorType) -> bool {
-        match actor_type {
-            &ActorType::Raincloud => {
-                self.user_spawns
-                    .get(&ActorType::Raincloud)
-                    .map(|v| v.len())
-                    .unwrap_or(0)
-                    // At most 2 can be spawned at any given time.
-                    < 2
-            }
-            _ => false,
-        }
+    /// Returns if users may spawn another actor of the given type, following `[spawns.limits]` and
+    /// the active event.
+    pub fn can_spawn_user_actor(&self, config: &Config, actor_type: &ActorType) -> bool {
+        let count = self.user_spawns.get(actor_type).map(|v| v.len()).unwrap_or(0);
+        count < self.spawns_config(config).limit(actor_type)
     }
 
     /// Spawns a user triggered raincloud. This will broadcast the spawn to all clients.
@@ -514,9 +808,139 @@ impl SpawnManager {
         })
     }
 
-    pub fn random_spawn_point(&self, group: &str) -> Option<&Vector3> {
-        self.spawn_points
-            .get(group)?
-            .choose(&mut rand::thread_rng())
+    pub fn random_spawn_point(&self, rng: &mut GameRng, group: &str) -> Option<&Vector3> {
+        self.spawn_points.get(group)?.choose(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        config::Config,
+        game::{actor::ActorType, clock::Clock, Game},
+        packet::variant::Vector3,
+        server::Server,
+        simulation::{headless_server, remove_store_dir, temp_store_dir},
+        TICK_MS,
+    };
+
+    fn seeded_game(name: &str, seed: u64) -> (Server, Game) {
+        let config = Config {
+            seed: Some(seed),
+            ..Config::default()
+        };
+        let (server, _) = headless_server(config, &temp_store_dir(name));
+        let game = Game::new(&server.config, Clock::manual());
+
+        (server, game)
+    }
+
+    /// Runs the game tick by tick and returns the actors alive after every simulated minute.
+    fn spawn_sequence(name: &str, seed: u64, minutes: u64) -> Vec<Vec<String>> {
+        let (mut server, mut game) = seeded_game(name, seed);
+        game.on_ready(&mut server);
+        let tick = Duration::from_millis(TICK_MS as u64);
+        let ticks_per_minute = 60_000 / TICK_MS as u64;
+        let mut sequence = vec![];
+        for _ in 0..minutes {
+            for _ in 0..ticks_per_minute {
+                game.clock.advance(tick);
+                game.on_update(&mut server);
+            }
+            let mut actors: Vec<String> = game
+                .actor_manager
+                .get_all_actors()
+                .iter()
+                .map(|actor| format!("{actor:?}"))
+                .collect();
+            actors.sort();
+            sequence.push(actors);
+        }
+        drop(server);
+        remove_store_dir(&temp_store_dir(name));
+
+        sequence
+    }
+
+    #[test]
+    fn same_seed_gives_same_spawns() {
+        let first = spawn_sequence("test-spawn-seed-a", 42, 30);
+        let second = spawn_sequence("test-spawn-seed-b", 42, 30);
+
+        assert!(first.iter().any(|actors| actors.len() > 4));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn void_portal_despawns_after_lifetime() {
+        let name = "test-spawn-void-portal";
+        let (mut server, mut game) = seeded_game(name, 1);
+        let config = server.config.spawns.clone();
+        game.spawn_manager.spawn_game_void_portal(
+            &mut server,
+            &mut game.actor_manager,
+            &mut game.rng,
+            &config,
+        );
+        let portal_id = game
+            .actor_manager
+            .get_actors_by_type(&ActorType::VoidPortal)[0]
+            .id;
+
+        game.clock.advance(Duration::from_secs(599));
+        game.on_update(&mut server);
+        assert!(game.actor_manager.get_actor(&portal_id).is_some());
+
+        game.clock.advance(Duration::from_secs(2));
+        game.on_update(&mut server);
+        assert!(game.actor_manager.get_actor(&portal_id).is_none());
+
+        drop(server);
+        remove_store_dir(&temp_store_dir(name));
+    }
+
+    #[test]
+    fn admin_spawns_do_not_count_towards_user_limits() {
+        let name = "test-spawn-admin";
+        let (mut server, mut game) = seeded_game(name, 1);
+        let position = Vector3 {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        for _ in 0..server.config.spawns.limit(&ActorType::Raincloud) {
+            let id = game.spawn_manager.spawn_admin_actor(
+                &mut server,
+                &mut game.actor_manager,
+                &mut game.rng,
+                ActorType::Raincloud,
+                "main_zone",
+                &position,
+            );
+            assert!(id.is_some());
+        }
+        assert!(game
+            .spawn_manager
+            .can_spawn_user_actor(&server.config, &ActorType::Raincloud));
+
+        drop(server);
+        remove_store_dir(&temp_store_dir(name));
+    }
+
+    #[test]
+    fn event_duration_which_overflows_is_rejected() {
+        let name = "test-spawn-event-overflow";
+        let (mut server, mut game) = seeded_game(name, 1);
+
+        let result = game
+            .spawn_manager
+            .start_event(&mut server, "storm", Duration::MAX);
+        assert!(result.is_err());
+        assert!(game.spawn_manager.event().is_none());
+
+        drop(server);
+        remove_store_dir(&temp_store_dir(name));
     }
 }
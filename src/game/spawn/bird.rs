@@ -0,0 +1,123 @@
+use std::{
+    f64::consts::TAU,
+    time::{Duration, Instant},
+};
+
+use log::debug;
+
+use crate::{
+    game::actor::{ActorManager, ActorType},
+    packet::variant::Vector3,
+    random::{godot_rand_range, GameRng},
+    server::Server,
+};
+
+use super::{SpawnManager, TAG};
+
+/// Birds fly off when a player comes within this distance.
+static FLEE_RANGE: f64 = 6.0;
+/// How fast a fleeing bird moves away from the player, in units per second.
+static FLEE_SPEED: f64 = 8.0;
+/// How fast a fleeing bird climbs, in units per second.
+static FLEE_CLIMB_SPEED: f64 = 4.0;
+/// How long a bird flies before it despawns.
+static FLEE_DURATION: Duration = Duration::from_secs(3);
+
+/// A bird which was scared off by a player.
+pub(super) struct FleeingBird {
+    velocity: Vector3,
+    despawn_at: Instant,
+}
+
+impl SpawnManager {
+    /// Scares off host birds which have a player nearby, moves fleeing birds and despawns the ones
+    /// which have flown for `FLEE_DURATION`. The ambient timer spawns new birds in their place.
+    pub(super) fn update_birds(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        rng: &mut GameRng,
+        elapsed: Duration,
+    ) {
+        let host_steam_id = server.host_steam_id;
+        let players: Vec<(String, Vector3)> = actor_manager
+            .get_actors_by_type(&ActorType::Player)
+            .iter()
+            .map(|player| (player.zone.clone(), player.position.clone()))
+            .collect();
+        let mut birds: Vec<(i64, String, Vector3)> = actor_manager
+            .get_actors_by_type(&ActorType::AmbientBird)
+            .iter()
+            .filter(|bird| bird.creator_id == host_steam_id)
+            .map(|bird| (bird.id, bird.zone.clone(), bird.position.clone()))
+            .collect();
+        // Sorted so flee directions are drawn from `rng` in the same order on every run.
+        birds.sort_unstable_by_key(|(id, _, _)| *id);
+
+        let mut flown_off = vec![];
+        for (id, zone, position) in birds {
+            if !self.fleeing_birds.contains_key(&id) {
+                let nearest_player = players
+                    .iter()
+                    .filter(|(player_zone, _)| *player_zone == zone)
+                    .map(|(_, player_position)| {
+                        (
+                            horizontal_distance(&position, player_position),
+                            player_position,
+                        )
+                    })
+                    .filter(|(distance, _)| *distance < FLEE_RANGE)
+                    .min_by(|(a, _), (b, _)| a.total_cmp(b));
+                let Some((_, player_position)) = nearest_player else {
+                    continue;
+                };
+
+                debug!(target: TAG, "Bird is fleeing from a player: actor_id = {id}");
+                let (dx, dz) = (
+                    position.x - player_position.x,
+                    position.z - player_position.z,
+                );
+                // Fly straight away from the player, or anywhere if they are on top of the bird.
+                let direction = if dx == 0.0 && dz == 0.0 {
+                    godot_rand_range(rng, 0.0, TAU)
+                } else {
+                    dz.atan2(dx)
+                };
+                self.fleeing_birds.insert(
+                    id,
+                    FleeingBird {
+                        velocity: Vector3 {
+                            x: direction.cos() * FLEE_SPEED,
+                            y: FLEE_CLIMB_SPEED,
+                            z: direction.sin() * FLEE_SPEED,
+                        },
+                        despawn_at: self.now + FLEE_DURATION,
+                    },
+                );
+            }
+
+            let fleeing_bird = &self.fleeing_birds[&id];
+            if self.now >= fleeing_bird.despawn_at {
+                flown_off.push(id);
+                continue;
+            }
+            let Some(bird) = actor_manager.get_actor_mut(&id) else {
+                continue;
+            };
+            let secs = elapsed.as_secs_f64();
+            bird.position.x += fleeing_bird.velocity.x * secs;
+            bird.position.y += fleeing_bird.velocity.y * secs;
+            bird.position.z += fleeing_bird.velocity.z * secs;
+            actor_manager.mark_dirty(&id);
+        }
+
+        for id in flown_off {
+            debug!(target: TAG, "Despawning bird which flew off: actor_id = {id}");
+            self.despawn_actor(server, actor_manager, &id);
+        }
+    }
+}
+
+fn horizontal_distance(a: &Vector3, b: &Vector3) -> f64 {
+    (a.x - b.x).hypot(a.z - b.z)
+}
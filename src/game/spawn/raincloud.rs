@@ -0,0 +1,80 @@
+use std::{f64::consts::PI, ops::Range, time::Duration};
+
+use log::debug;
+
+use crate::{
+    config::SpawnsConfig,
+    game::actor::{ActorManager, ActorType},
+    packet::variant::Vector3,
+    server::Server,
+};
+
+use super::{SpawnManager, TAG};
+
+/// Rainclouds only drift in this zone. The bounds and the center below are in its coordinates.
+static DRIFT_ZONE: &str = "main_zone";
+/// Rainclouds which drift outside these x and z ranges have left the map and despawn.
+static BOUNDS_X: Range<f64> = -150.0..200.0;
+static BOUNDS_Z: Range<f64> = -200.0..150.0;
+/// The x and z of the point the game's raincloud script (`raincloud.gd`) steers clouds across.
+static MAP_CENTER: (f64, f64) = (30.0, -50.0);
+
+impl SpawnManager {
+    /// Moves host rainclouds in `main_zone` along their drift direction, which is set the first
+    /// time a raincloud moves. Rainclouds which leave the map are despawned. Rainclouds in other
+    /// zones, such as one from `!rain` inside a building, stay in place.
+    pub(super) fn drift_rainclouds(
+        &mut self,
+        server: &mut Server,
+        actor_manager: &mut ActorManager,
+        config: &SpawnsConfig,
+        elapsed: Duration,
+    ) {
+        let distance = config.raincloud_speed * elapsed.as_secs_f64();
+        if distance <= 0.0 {
+            return;
+        }
+
+        let host_steam_id = server.host_steam_id;
+        let mut raincloud_ids: Vec<i64> = actor_manager
+            .get_actors_by_type(&ActorType::Raincloud)
+            .iter()
+            .filter(|actor| actor.creator_id == host_steam_id && actor.zone == DRIFT_ZONE)
+            .map(|actor| actor.id)
+            .collect();
+        // Sorted so rainclouds move and despawn in the same order on every run.
+        raincloud_ids.sort_unstable();
+        let mut left_map = vec![];
+        for id in raincloud_ids {
+            let Some(raincloud) = actor_manager.get_actor_mut(&id) else {
+                continue;
+            };
+            let direction = *self
+                .raincloud_directions
+                .entry(id)
+                .or_insert_with(|| drift_direction(&raincloud.position));
+            let position = &mut raincloud.position;
+            position.x += direction.cos() * distance;
+            position.z += direction.sin() * distance;
+            if BOUNDS_X.contains(&position.x) && BOUNDS_Z.contains(&position.z) {
+                actor_manager.mark_dirty(&id);
+            } else {
+                left_map.push(id);
+            }
+        }
+
+        for id in left_map {
+            debug!(target: TAG, "Despawning raincloud which left the map: actor_id = {id}");
+            self.despawn_actor(server, actor_manager, &id);
+        }
+    }
+}
+
+/// Returns the drift direction as an angle in the x/z plane, the way `raincloud.gd` sets it when a
+/// cloud spawns: it takes the angle from the map center to the cloud and moves along
+/// `Vector2(-1, 0)` rotated by it, so every cloud heads across the center and off the far side.
+fn drift_direction(position: &Vector3) -> f64 {
+    let angle = (position.z - MAP_CENTER.1).atan2(position.x - MAP_CENTER.0);
+    // (-1, 0) rotated by `angle` points the opposite way, back toward the center.
+    angle + PI
+}
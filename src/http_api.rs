@@ -0,0 +1,332 @@
+use std::{
+    io::Write,
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread,
+    time::Duration,
+};
+
+use log::{info, warn};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::{config::HttpApiConfig, event::EventSubscriber, packet::variant::Vector3};
+
+pub mod handler;
+
+static TAG: &str = "http_api";
+/// How long the HTTP thread waits for the game thread to handle a request.
+static RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often a comment is sent on an idle event stream, which also detects closed connections.
+static EVENT_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// An API call parsed from an HTTP request, to be run on the game thread.
+#[derive(Debug)]
+pub enum ApiRequest {
+    Players,
+    Actors,
+    Lobby,
+    Broadcast {
+        message: String,
+    },
+    Kick {
+        steam_id: u64,
+    },
+    Ban {
+        steam_id: u64,
+        reason: Option<String>,
+        /// A duration such as `2h`. `None` bans permanently.
+        duration: Option<String>,
+    },
+    Spawn {
+        actor_type: String,
+        zone: String,
+        position: Vector3,
+    },
+    Reload,
+    Metrics,
+}
+
+pub struct ApiResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl ApiResponse {
+    pub fn ok(body: Value) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json",
+            body: body.to_string(),
+        }
+    }
+
+    pub fn text(body: String, content_type: &'static str) -> Self {
+        Self {
+            status: 200,
+            content_type,
+            body,
+        }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: json!({ "error": message }).to_string(),
+        }
+    }
+}
+
+/// An API call waiting to be handled by the game thread. The response is sent back to the HTTP
+/// thread through `sender_response`.
+pub struct HttpApiRequest {
+    pub request: ApiRequest,
+    pub sender_response: Sender<ApiResponse>,
+}
+
+#[derive(Deserialize)]
+struct BroadcastBody {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct KickBody {
+    steam_id: u64,
+}
+
+#[derive(Deserialize)]
+struct BanBody {
+    steam_id: u64,
+    reason: Option<String>,
+    duration: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SpawnBody {
+    actor_type: String,
+    zone: Option<String>,
+    position: Vector3,
+}
+
+/// Starts the HTTP API on a separate thread. Requests are handed to the game thread through
+/// `sender_http_api`, and event streams subscribe through `sender_event_subscriber`.
+pub fn init_http_api(
+    config: &HttpApiConfig,
+    sender_http_api: Sender<HttpApiRequest>,
+    sender_event_subscriber: Sender<EventSubscriber>,
+) {
+    if config.token.is_empty() {
+        info!(target: TAG, "Not starting HTTP API: http_api.token must be set");
+        return;
+    }
+    let http_server = match tiny_http::Server::http(&config.bind) {
+        Ok(http_server) => http_server,
+        Err(e) => {
+            warn!(
+                target: TAG,
+                "Failed starting HTTP API: bind = {}, error = {e}",
+                config.bind
+            );
+            return;
+        }
+    };
+    info!(target: TAG, "HTTP API listening: bind = {}", config.bind);
+
+    let token = config.token.clone();
+    thread::spawn(move || {
+        for request in http_server.incoming_requests() {
+            on_http_request(&token, &sender_http_api, &sender_event_subscriber, request);
+        }
+    });
+}
+
+fn on_http_request(
+    token: &str,
+    sender_http_api: &Sender<HttpApiRequest>,
+    sender_event_subscriber: &Sender<EventSubscriber>,
+    mut request: Request,
+) {
+    if !is_authorized(token, &request) {
+        respond(request, ApiResponse::error(401, "Missing or invalid token"));
+        return;
+    }
+    if *request.method() == Method::Get && url_path(request.url()) == "/api/events" {
+        stream_events(sender_event_subscriber, request);
+        return;
+    }
+
+    let mut body = String::new();
+    let _ = request.as_reader().read_to_string(&mut body);
+    let response = match parse_request(request.method(), request.url(), &body) {
+        Ok(api_request) => send_to_game_thread(sender_http_api, api_request),
+        Err(response) => response,
+    };
+    respond(request, response);
+}
+
+fn respond(request: Request, response: ApiResponse) {
+    let status = response.status;
+    let content_type = Header::from_bytes("Content-Type", response.content_type).unwrap();
+    let result = request.respond(
+        Response::from_string(response.body)
+            .with_status_code(status)
+            .with_header(content_type),
+    );
+    if let Err(e) = result {
+        warn!(target: TAG, "Failed sending response: status = {status}, error = {e}");
+    }
+}
+
+/// Checks the `Authorization: Bearer <token>` header. The comparison takes the same time no matter
+/// where the tokens differ.
+fn is_authorized(token: &str, request: &Request) -> bool {
+    let Some(header) = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+    else {
+        return false;
+    };
+    let Some(given) = header.value.as_str().strip_prefix("Bearer ") else {
+        return false;
+    };
+
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn parse_request(method: &Method, url: &str, body: &str) -> Result<ApiRequest, ApiResponse> {
+    match (method, url_path(url)) {
+        (Method::Get, "/api/players") => Ok(ApiRequest::Players),
+        (Method::Get, "/api/actors") => Ok(ApiRequest::Actors),
+        (Method::Get, "/api/lobby") => Ok(ApiRequest::Lobby),
+        (Method::Post, "/api/broadcast") => {
+            let body: BroadcastBody = parse_body(body)?;
+            Ok(ApiRequest::Broadcast {
+                message: body.message,
+            })
+        }
+        (Method::Post, "/api/kick") => {
+            let body: KickBody = parse_body(body)?;
+            Ok(ApiRequest::Kick {
+                steam_id: body.steam_id,
+            })
+        }
+        (Method::Post, "/api/ban") => {
+            let body: BanBody = parse_body(body)?;
+            Ok(ApiRequest::Ban {
+                steam_id: body.steam_id,
+                reason: body.reason,
+                duration: body.duration,
+            })
+        }
+        (Method::Post, "/api/spawn") => {
+            let body: SpawnBody = parse_body(body)?;
+            Ok(ApiRequest::Spawn {
+                actor_type: body.actor_type,
+                zone: body.zone.unwrap_or("main_zone".to_owned()),
+                position: body.position,
+            })
+        }
+        (Method::Post, "/api/reload") => Ok(ApiRequest::Reload),
+        (Method::Get, "/metrics") => Ok(ApiRequest::Metrics),
+        (
+            _,
+            "/api/players" | "/api/actors" | "/api/lobby" | "/api/broadcast" | "/api/kick"
+            | "/api/ban" | "/api/spawn" | "/api/reload" | "/api/events" | "/metrics",
+        ) => Err(ApiResponse::error(405, "Method not allowed")),
+        _ => Err(ApiResponse::error(404, "Not found")),
+    }
+}
+
+/// Returns the path of a request URL without the query or a trailing slash.
+fn url_path(url: &str) -> &str {
+    url.split('?').next().unwrap_or(url).trim_end_matches('/')
+}
+
+/// Returns the value of a query parameter. Values are not percent-decoded, except for commas.
+fn query_param(url: &str, name: &str) -> Option<String> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.replace("%2C", ",").replace("%2c", ","))
+}
+
+/// Streams events to the client as Server-Sent Events until it disconnects. The `types` query
+/// parameter filters events by a comma separated list of types.
+fn stream_events(sender_event_subscriber: &Sender<EventSubscriber>, request: Request) {
+    let types = query_param(request.url(), "types").map(|types| {
+        types
+            .split(',')
+            .filter(|event_type| !event_type.is_empty())
+            .map(str::to_owned)
+            .collect()
+    });
+    let (sender_event, receiver_event) = mpsc::channel();
+    let subscriber = EventSubscriber::new(types, sender_event);
+    if sender_event_subscriber.send(subscriber).is_err() {
+        respond(request, ApiResponse::error(503, "Server is shutting down"));
+        return;
+    }
+
+    // Each stream gets its own thread so it doesn't block other requests.
+    thread::spawn(move || {
+        let mut writer = request.into_writer();
+        let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+            Cache-Control: no-cache\r\nConnection: close\r\n\r\n";
+        if writer
+            .write_all(head.as_bytes())
+            .and_then(|_| writer.flush())
+            .is_err()
+        {
+            return;
+        }
+        loop {
+            let chunk = match receiver_event.recv_timeout(EVENT_KEEP_ALIVE_INTERVAL) {
+                Ok((event_type, json)) => format!("event: {event_type}\ndata: {json}\n\n"),
+                Err(RecvTimeoutError::Timeout) => ": keep-alive\n\n".to_owned(),
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            if writer
+                .write_all(chunk.as_bytes())
+                .and_then(|_| writer.flush())
+                .is_err()
+            {
+                break;
+            }
+        }
+        info!(target: TAG, "Event stream closed");
+    });
+}
+
+fn parse_body<T: DeserializeOwned>(body: &str) -> Result<T, ApiResponse> {
+    serde_json::from_str(body)
+        .map_err(|e| ApiResponse::error(400, &format!("Invalid request body: {e}")))
+}
+
+/// Hands the request to the game thread and waits for its response.
+fn send_to_game_thread(
+    sender_http_api: &Sender<HttpApiRequest>,
+    request: ApiRequest,
+) -> ApiResponse {
+    let (sender_response, receiver_response) = mpsc::channel();
+    let request = HttpApiRequest {
+        request,
+        sender_response,
+    };
+    if sender_http_api.send(request).is_err() {
+        return ApiResponse::error(503, "Server is shutting down");
+    }
+
+    receiver_response
+        .recv_timeout(RESPONSE_TIMEOUT)
+        .unwrap_or(ApiResponse::error(504, "Timed out waiting for the server"))
+}
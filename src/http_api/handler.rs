@@ -0,0 +1,206 @@
+use log::debug;
+use serde_json::{json, Value};
+use steamworks::SteamId;
+
+use crate::{
+    game::{
+        actor::{Actor, ActorType},
+        Game,
+    },
+    moderation,
+    packet::variant::Vector3,
+    server::Server,
+    time::{format_rfc3339_utc, parse_duration},
+};
+
+use super::{ApiRequest, ApiResponse, HttpApiRequest};
+
+static TAG: &str = "http_api::handler";
+
+/// Runs an API call on the game thread and sends the response back to the HTTP thread.
+pub fn on_http_api_request(server: &mut Server, game: &mut Game, http_api_request: HttpApiRequest) {
+    debug!(target: TAG, "Handling request: {:?}", http_api_request.request);
+    let response = match http_api_request.request {
+        ApiRequest::Players => handle_players(server, game),
+        ApiRequest::Actors => handle_actors(server, game),
+        ApiRequest::Lobby => handle_lobby(server),
+        ApiRequest::Broadcast { message } => handle_broadcast(server, &message),
+        ApiRequest::Kick { steam_id } => handle_kick(server, game, steam_id),
+        ApiRequest::Ban {
+            steam_id,
+            reason,
+            duration,
+        } => handle_ban(server, game, steam_id, reason, duration),
+        ApiRequest::Spawn {
+            actor_type,
+            zone,
+            position,
+        } => handle_spawn(server, game, &actor_type, &zone, position),
+        ApiRequest::Reload => handle_reload(server),
+        ApiRequest::Metrics => handle_metrics(server, game),
+    };
+
+    // The HTTP thread may have given up waiting, in which case there is nobody to respond to.
+    let _ = http_api_request.sender_response.send(response);
+}
+
+fn handle_players(server: &Server, game: &Game) -> ApiResponse {
+    let players: Vec<Value> = server
+        .players
+        .iter()
+        .map(|player| {
+            let player_actor = game.actor_manager.get_player_actor(&player.steam_id);
+            json!({
+                "steam_id": player.steam_id.raw(),
+                "name": player.name,
+                "role": player.role,
+                "admin": server.is_admin(&player.steam_id),
+                "joined_at": format_rfc3339_utc(player.joined_at_unix),
+                "session_secs": player.session_duration().as_secs(),
+                "actor_id": player.actor_id,
+                "last_packet_secs_ago": player.last_packet_at.map(|at| at.elapsed().as_secs()),
+                "ping_ms": player.ping.map(|ping| ping.as_millis() as u64),
+                "zone": player_actor.map(|actor| &actor.zone),
+                "position": player_actor.map(|actor| &actor.position),
+            })
+        })
+        .collect();
+
+    ApiResponse::ok(json!({ "players": players }))
+}
+
+fn handle_actors(server: &Server, game: &Game) -> ApiResponse {
+    let host_steam_id = server.host_steam_id;
+    let actors: Vec<Value> = game
+        .actor_manager
+        .get_all_actors()
+        .into_iter()
+        .map(|actor| actor_to_json(actor, &host_steam_id))
+        .collect();
+
+    ApiResponse::ok(json!({ "actors": actors }))
+}
+
+fn handle_lobby(server: &Server) -> ApiResponse {
+    let config = &server.config;
+    ApiResponse::ok(json!({
+        "lobby_id": server.lobby_id.map(|lobby_id| lobby_id.raw()),
+        "name": config.name,
+        "code": config.lobby_code,
+        "players": server.players.count(),
+        "max_players": config.max_players,
+        "unlisted": config.unlisted,
+        "bans": server.ban_list.count(),
+        "mutes": server.mute_list.count(),
+    }))
+}
+
+fn handle_broadcast(server: &Server, message: &str) -> ApiResponse {
+    if message.trim().is_empty() {
+        return ApiResponse::error(400, "Message is empty");
+    }
+    server.broadcast_chat_message(message);
+
+    ApiResponse::ok(json!({}))
+}
+
+fn handle_kick(server: &mut Server, game: &mut Game, steam_id: u64) -> ApiResponse {
+    let steam_id = SteamId::from_raw(steam_id);
+    if let Err(response) = check_target(server, &steam_id) {
+        return response;
+    }
+    if !server.players.contains(&steam_id) {
+        return ApiResponse::error(404, "Player is not in the lobby");
+    }
+    moderation::kick_player(server, game, &steam_id);
+
+    ApiResponse::ok(json!({}))
+}
+
+fn handle_ban(
+    server: &mut Server,
+    game: &mut Game,
+    steam_id: u64,
+    reason: Option<String>,
+    duration: Option<String>,
+) -> ApiResponse {
+    let steam_id = SteamId::from_raw(steam_id);
+    if let Err(response) = check_target(server, &steam_id) {
+        return response;
+    }
+    let duration = match duration.as_deref().map(parse_duration) {
+        None => None,
+        Some(Some(duration)) => Some(duration),
+        Some(None) => return ApiResponse::error(400, "Invalid duration, e.g. 30m, 2h or 7d"),
+    };
+    moderation::ban_player(server, game, &steam_id, reason, duration);
+
+    ApiResponse::ok(json!({}))
+}
+
+fn handle_spawn(
+    server: &mut Server,
+    game: &mut Game,
+    actor_type: &str,
+    zone: &str,
+    position: Vector3,
+) -> ApiResponse {
+    let actor_type = ActorType::from(actor_type);
+    if !actor_type.is_known() || actor_type == ActorType::Player {
+        return ApiResponse::error(400, "Unknown or unsupported actor_type");
+    }
+    let Some(actor_id) = game.spawn_manager.spawn_admin_actor(
+        server,
+        &mut game.actor_manager,
+        &mut game.rng,
+        actor_type,
+        zone,
+        &position,
+    ) else {
+        return ApiResponse::error(409, "The host has too many actors");
+    };
+
+    let host_steam_id = server.host_steam_id;
+    match game.actor_manager.get_actor(&actor_id) {
+        Some(actor) => ApiResponse::ok(actor_to_json(actor, &host_steam_id)),
+        None => ApiResponse::ok(json!({ "id": actor_id })),
+    }
+}
+
+fn handle_reload(server: &mut Server) -> ApiResponse {
+    match crate::reload_config(server) {
+        Ok(()) => ApiResponse::ok(json!({})),
+        Err(e) => ApiResponse::error(500, &format!("Failed reloading config.toml: {e}")),
+    }
+}
+
+fn handle_metrics(server: &Server, game: &Game) -> ApiResponse {
+    let body = server.metrics.render(
+        server.players.count(),
+        &game.actor_manager.get_all_actors(),
+        &server.host_steam_id,
+    );
+
+    ApiResponse::text(body, "text/plain; version=0.0.4")
+}
+
+/// Checks that a moderation action may target the given player. Admins, including the host, can't
+/// be targeted.
+fn check_target(server: &Server, steam_id: &SteamId) -> Result<(), ApiResponse> {
+    if server.is_admin(steam_id) {
+        return Err(ApiResponse::error(403, "Cannot target an admin"));
+    }
+
+    Ok(())
+}
+
+fn actor_to_json(actor: &Actor, host_steam_id: &SteamId) -> Value {
+    json!({
+        "id": actor.id,
+        "actor_type": String::from(actor.actor_type.clone()),
+        "creator_id": actor.creator_id.raw(),
+        "host": actor.creator_id == *host_steam_id,
+        "zone": actor.zone,
+        "position": actor.position,
+    })
+}
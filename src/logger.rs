@@ -0,0 +1,257 @@
+use std::{
+    cmp::Reverse,
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::LoggingConfig,
+    time::{format_rfc3339_utc, system_time_since_unix_epoch_seconds},
+};
+
+/// Overrides the `level` field of the `[logging]` config section when set.
+static LOG_FILTER_ENV: &str = "RUST_LOG";
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// `2024-12-01T13:37:00Z INFO  [game::spawn] Spawning...`
+    #[default]
+    Text,
+    /// One JSON object per line with `timestamp`, `level`, `target` and `message` fields.
+    Json,
+}
+
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    timestamp: String,
+    level: &'a str,
+    target: &'a str,
+    message: String,
+}
+
+/// A RUST_LOG-style filter such as `info,game::spawn=debug,actor_update=off`. Targets are the
+/// `TAG` of each module, and a directive also applies to targets nested under it, e.g. `game`
+/// applies to `game::spawn`. The most specific directive wins.
+struct LogFilter {
+    default: LevelFilter,
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    fn parse(spec: &str) -> Self {
+        let mut default = LevelFilter::Info;
+        let mut directives = vec![];
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => match level.trim().parse() {
+                    Ok(level) => directives.push((target.trim().to_owned(), level)),
+                    Err(_) => eprintln!("Ignoring invalid log directive: {directive}"),
+                },
+                None => match directive.parse() {
+                    Ok(level) => default = level,
+                    // A bare target enables everything for it.
+                    Err(_) => directives.push((directive.to_owned(), LevelFilter::Trace)),
+                },
+            }
+        }
+        // Longest targets first so the most specific directive is found first.
+        directives.sort_by_key(|(target, _)| Reverse(target.len()));
+
+        Self {
+            default,
+            directives,
+        }
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .find(|(directive, _)| {
+                target == directive
+                    || target
+                        .strip_prefix(directive.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+/// A log file which is rotated once it grows past `max_file_bytes`. Rotated files are renamed to
+/// `<path>.1`, `<path>.2` and so on, keeping at most `max_files` of them.
+struct LogFile {
+    path: PathBuf,
+    max_file_bytes: u64,
+    max_files: u32,
+    file: Option<File>,
+    size: u64,
+}
+
+impl LogFile {
+    fn new(path: &str, max_file_bytes: u64, max_files: u32) -> Self {
+        Self {
+            path: PathBuf::from(path),
+            max_file_bytes,
+            max_files,
+            file: None,
+            size: 0,
+        }
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.max_file_bytes > 0
+            && self.size > 0
+            && self.size + line.len() as u64 > self.max_file_bytes
+        {
+            self.file = None;
+            if let Err(e) = self.rotate() {
+                eprintln!(
+                    "Failed rotating log file: path = {}, error = {e}",
+                    self.path.display()
+                );
+            }
+        }
+        if self.file.is_none() {
+            if let Err(e) = self.open() {
+                eprintln!(
+                    "Failed opening log file: path = {}, error = {e}",
+                    self.path.display()
+                );
+                return;
+            }
+        }
+
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        if file.write_all(line.as_bytes()).is_ok() {
+            self.size += line.len() as u64;
+        }
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        self.file = Some(file);
+
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.max_files == 0 {
+            return fs::remove_file(&self.path);
+        }
+        for i in (1..self.max_files).rev() {
+            let from = rotated_path(&self.path, i);
+            if from.exists() {
+                fs::rename(from, rotated_path(&self.path, i + 1))?;
+            }
+        }
+
+        fs::rename(&self.path, rotated_path(&self.path, 1))
+    }
+}
+
+fn rotated_path(path: &Path, index: u32) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(format!(".{index}"));
+
+    PathBuf::from(rotated)
+}
+
+struct Logger {
+    filter: LogFilter,
+    format: LogFormat,
+    file: Option<Mutex<LogFile>>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let timestamp = format_rfc3339_utc(system_time_since_unix_epoch_seconds());
+        let line = match self.format {
+            LogFormat::Text => format!(
+                "{timestamp} {:<5} [{}] {}\n",
+                record.level(),
+                record.target(),
+                record.args()
+            ),
+            LogFormat::Json => {
+                let json_line = JsonLine {
+                    timestamp,
+                    level: record.level().as_str(),
+                    target: record.target(),
+                    message: record.args().to_string(),
+                };
+                match serde_json::to_string(&json_line) {
+                    Ok(json) => json + "\n",
+                    Err(_) => return,
+                }
+            }
+        };
+
+        let _ = io::stdout().write_all(line.as_bytes());
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                file.write_line(&line);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        let _ = io::stdout().flush();
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                if let Some(file) = file.file.as_mut() {
+                    let _ = file.flush();
+                }
+            }
+        }
+    }
+}
+
+/// Installs the global logger. The filter is taken from the `RUST_LOG` environment variable if it
+/// is set, otherwise from `level` in the config.
+pub fn init_logger(config: &LoggingConfig) {
+    let spec = env::var(LOG_FILTER_ENV).unwrap_or(config.level.clone());
+    let filter = LogFilter::parse(&spec);
+    let max_level = filter.max_level();
+    let logger = Logger {
+        filter,
+        format: config.format,
+        file: Some(config.file.as_str())
+            .filter(|path| !path.is_empty())
+            .map(|path| Mutex::new(LogFile::new(path, config.max_file_bytes, config.max_files))),
+    };
+
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
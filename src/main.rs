@@ -1,52 +1,80 @@
 use std::{
-    fs, io,
+    env, io,
     sync::mpsc::{self, Sender},
     thread,
     time::{Duration, Instant},
 };
 
+use audit::AuditEvent;
 use config::Config;
-use game::Game;
+use event::TickMetrics;
+use game::{clock::Clock, Game};
+use log::{debug, error, info, warn};
 use packet::{
     on_receive_packet, on_send_packet,
-    util::{
-        build_force_disconnect_player_packet, build_handshake_packet,
-        build_user_joined_weblobby_packet, send_variant_p2p,
-    },
+    util::{build_handshake_packet, build_user_joined_weblobby_packet, send_variant_p2p},
     OutgoingP2pPacketRequest, P2pChannel, P2pPacketTarget,
 };
+use player::Presence;
 use server::Server;
 use steamworks::{
     networking_messages::SessionRequest, ChatMemberStateChange, Client, ClientManager,
-    LobbyChatMsg, LobbyChatUpdate, LobbyId, LobbyType, Matchmaking, SendType,
+    LobbyChatMsg, LobbyChatUpdate, LobbyId, LobbyType, PersonaStateChange, SendType,
 };
-use time::system_time_since_unix_epoch_seconds;
+use time::{parse_duration, system_time_since_unix_epoch_seconds};
 
+mod announcement;
+mod audit;
 mod command;
 mod config;
+mod console;
+mod event;
 mod game;
+mod http_api;
+mod logger;
+mod metrics;
+mod moderation;
+mod motd;
 mod packet;
+mod persist;
+mod player;
 mod random;
 mod server;
+mod simulation;
+mod template;
 mod time;
 
 static TAG: &str = "ducky";
 static WF_APP_ID: u32 = 3146520;
 static TICK_MS: u128 = 1000 / 16; // 16 ticks/s
 static LOBBY_UPDATE_INTERVAL_SEC: u64 = 20; // 20 seconds
+static CONFIG_RELOAD_INTERVAL_SEC: u64 = 5; // 5 seconds
 
 fn main() {
     let server_epoch = Instant::now();
     println!("(o< (o< (o< (o< (o<\n<_) <_) <_) <_) <_)");
 
-    let config = match read_config() {
-        Ok(config) => config,
-        Err(e) => {
-            println!("[{TAG}] Failed reading config.toml, using defaults. error = {e}");
-            Config::default()
-        }
+    let mut config_modified_time = Config::modified_time();
+    // The logger is configured from config.toml, so any error is logged once it is installed.
+    let (config, config_error) = match Config::load(None) {
+        Ok(config) => (config, None),
+        Err(e) => (Config::default(), Some(e)),
     };
-    println!("[{TAG}] Using config: config = {config:?}");
+    logger::init_logger(&config.logging);
+    if let Some(e) = config_error {
+        warn!(target: TAG, "Failed reading config.toml, using defaults. error = {e}");
+    }
+    info!(target: TAG, "Using config: config = {config:?}");
+
+    let args: Vec<String> = env::args().collect();
+    if let Some(index) = args.iter().position(|arg| arg == "--simulate") {
+        let Some(duration) = args.get(index + 1).and_then(|arg| parse_duration(arg)) else {
+            error!(target: TAG, "Usage: ducky --simulate <duration, e.g. 30m, 24h, 7d>");
+            return;
+        };
+        simulation::run(config, duration);
+        return;
+    }
 
     let client = init_steam_client();
     let (sender_create_lobby, receiver_create_lobby) = mpsc::channel();
@@ -54,6 +82,10 @@ fn main() {
     let (sender_lobby_chat_msg, receiver_lobby_chat_msg) = mpsc::channel();
     let (sender_net_session, receiver_net_session) = mpsc::channel();
     init_steam_networking(&client, sender_net_session);
+    let (sender_persona_state_change, receiver_persona_state_change) = mpsc::channel();
+    init_steam_friends(&client, sender_persona_state_change);
+    let (sender_console_line, receiver_console_line) = mpsc::channel();
+    console::init_console(sender_console_line);
     init_lobby(
         &client,
         &config,
@@ -63,27 +95,33 @@ fn main() {
     );
 
     let (sender_p2p_packet, receiver_p2p_packet) = mpsc::channel::<OutgoingP2pPacketRequest>();
-    let matchmaking = client.matchmaking();
     let networking_messages = client.networking_messages();
     let mut server = Server::new(client, sender_p2p_packet, config.clone());
-    server
-        .users
-        .insert(server.steam_client.user().steam_id().raw());
-    config
-        .ban_list
-        .iter()
-        .for_each(|id| server.insert_ban_list(*id));
-
-    let mut game = Game::new();
+    server.insert_player(server.host_steam_id);
+
+    let (sender_http_api, receiver_http_api) = mpsc::channel();
+    if config.http_api.enabled {
+        http_api::init_http_api(
+            &config.http_api,
+            sender_http_api,
+            server.events.subscriber_sender(),
+        );
+    }
+
+    let mut game = Game::new(&server.config, Clock::Real);
     game.on_ready(&mut server);
 
     let mut lobby_update_timer = Instant::now();
+    let mut config_reload_timer = Instant::now();
+    let mut tick_metrics = TickMetrics::new(Duration::from_millis(TICK_MS as u64));
 
     loop {
+        let tick_start = Instant::now();
         while let Ok(new_lobby_id) = receiver_create_lobby.try_recv() {
             // On lobby created
             server.set_lobby_id(new_lobby_id);
-            set_lobby_data(new_lobby_id, &matchmaking, 1, &config);
+            set_lobby_data(&mut server, new_lobby_id);
+            info!(target: TAG, "Lobby code: {}", server.config.lobby_code);
         }
         while let Ok(update) = receiver_lobby_chat_update.try_recv() {
             on_lobby_chat_update(&mut server, &mut game, update);
@@ -94,18 +132,46 @@ fn main() {
         while let Ok(session_request) = receiver_net_session.try_recv() {
             on_net_session_request(&mut server, session_request);
         }
+        while let Ok(change) = receiver_persona_state_change.try_recv() {
+            server.refresh_persona_name(&change.steam_id);
+        }
+        while let Ok(line) = receiver_console_line.try_recv() {
+            console::on_console_line(&mut server, &mut game, line);
+        }
+        while let Ok(request) = receiver_http_api.try_recv() {
+            http_api::handler::on_http_api_request(&mut server, &mut game, request);
+        }
+        if server.shutdown_requested {
+            server.broadcast_chat_message("The server is shutting down.");
+        }
         while let Ok(outgoing) = receiver_p2p_packet.try_recv() {
-            on_send_packet(&server, outgoing);
+            on_send_packet(&mut server, outgoing);
+        }
+        if server.shutdown_requested {
+            shutdown(&server);
+            break;
         }
 
         if lobby_update_timer.elapsed() > Duration::from_secs(LOBBY_UPDATE_INTERVAL_SEC) {
             if let Some(lobby_id) = server.lobby_id {
                 lobby_update_timer = Instant::now();
-                set_lobby_data(lobby_id, &matchmaking, server.users.len(), &config);
+                set_lobby_data(&mut server, lobby_id);
             }
         }
+        if config_reload_timer.elapsed() > Duration::from_secs(CONFIG_RELOAD_INTERVAL_SEC) {
+            config_reload_timer = Instant::now();
+            let modified_time = Config::modified_time();
+            if modified_time != config_modified_time {
+                config_modified_time = modified_time;
+                let _ = reload_config(&mut server);
+            }
+        }
+        moderation::on_update(&mut server);
+        player::on_update(&mut server);
+        motd::on_update(&mut server);
+        announcement::on_update(&mut server);
 
-        server.steam_client.run_callbacks();
+        server.steam().run_callbacks();
         for channel in P2pChannel::VALUES {
             let channel_u32 = channel as u32;
             loop {
@@ -115,7 +181,13 @@ fn main() {
                 }
                 for message in received {
                     if let Some(sender) = message.identity_peer().steam_id() {
-                        on_receive_packet(&mut server, &mut game, message.data().to_vec(), sender);
+                        on_receive_packet(
+                            &mut server,
+                            &mut game,
+                            message.data().to_vec(),
+                            sender,
+                            channel,
+                        );
                     }
                 }
             }
@@ -123,6 +195,17 @@ fn main() {
 
         game.on_update(&mut server);
 
+        server
+            .metrics
+            .record_tick(tick_start.elapsed(), Duration::from_millis(TICK_MS as u64));
+        if let Some(tick_event) = tick_metrics.record(
+            tick_start.elapsed(),
+            server.players.count(),
+            game.actor_manager.count_actors(),
+        ) {
+            server.events.publish(&tick_event);
+        }
+
         // TODO: Move loops into separate tasks or threads so they can all poll, only the Steam
         //  networking thread needs to sleep.
         let duration = TICK_MS - Instant::now().duration_since(server_epoch).as_millis() % TICK_MS;
@@ -130,18 +213,13 @@ fn main() {
     }
 }
 
-fn read_config() -> io::Result<Config> {
-    toml::from_str(&fs::read_to_string("config.toml")?)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
-}
-
 fn init_steam_client() -> Client {
-    println!("[{}] Initializing Steam...", TAG);
+    info!(target: TAG, "Initializing Steam...");
 
     let client = Client::init_app(WF_APP_ID)
         .expect("Steam is not detected or account does not own WEBFISHING.");
 
-    println!("[{}] Steam OK", TAG);
+    info!(target: TAG, "Steam OK");
     client
 }
 
@@ -157,6 +235,13 @@ fn init_steam_networking(
         });
 }
 
+/// Forwards persona changes, such as name changes, so the player registry can be updated.
+fn init_steam_friends(client: &Client, sender_persona_state_change: Sender<PersonaStateChange>) {
+    client.register_callback(move |change: PersonaStateChange| {
+        let _ = sender_persona_state_change.send(change);
+    });
+}
+
 fn init_lobby(
     client: &Client,
     config: &Config,
@@ -164,7 +249,7 @@ fn init_lobby(
     sender_lobby_chat_update: Sender<LobbyChatUpdate>,
     sender_lobby_chat_msg: Sender<LobbyChatMsg>,
 ) {
-    println!("[{}] Creating Steam lobby...", TAG);
+    info!(target: TAG, "Creating Steam lobby...");
 
     client
         .matchmaking()
@@ -174,9 +259,9 @@ fn init_lobby(
             move |result| match result {
                 Ok(lobby_id) => {
                     sender_create_lobby.send(lobby_id).unwrap();
-                    println!(
-                        "[{}] Steam lobby created: lobby_id = {}",
-                        TAG,
+                    info!(
+                        target: TAG,
+                        "Steam lobby created: lobby_id = {}",
                         lobby_id.raw()
                     )
                 }
@@ -193,18 +278,44 @@ fn init_lobby(
     });
 }
 
-fn set_lobby_data(
-    lobby_id: LobbyId,
-    matchmaking: &Matchmaking<ClientManager>,
-    user_count: usize,
-    config: &Config,
-) {
-    println!(
-        "[{}] Setting lobby fields: lobby_id = {}",
-        TAG,
+/// Reloads config.toml and updates the lobby fields to match.
+pub fn reload_config(server: &mut Server) -> io::Result<()> {
+    match server.reload_config() {
+        Ok(()) => {
+            info!(target: TAG, "Reloaded config.toml");
+            if let Some(lobby_id) = server.lobby_id {
+                set_lobby_data(server, lobby_id);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            warn!(target: TAG, "Failed reloading config.toml: error = {e}");
+            Err(e)
+        }
+    }
+}
+
+/// Leaves the lobby so players are returned to the menu instead of waiting for a timeout.
+fn shutdown(server: &Server) {
+    info!(target: TAG, "Stopping server");
+    // Give Steam a moment to flush the final packets before leaving.
+    server.steam().run_callbacks();
+    thread::sleep(Duration::from_millis(500));
+    if let Some(lobby_id) = server.lobby_id {
+        server.steam().matchmaking().leave_lobby(lobby_id);
+    }
+}
+
+fn set_lobby_data(server: &mut Server, lobby_id: LobbyId) {
+    server.metrics.record_lobby_data_refresh();
+    let matchmaking = server.steam().matchmaking();
+    let config = &server.config;
+    debug!(
+        target: TAG,
+        "Setting lobby fields: lobby_id = {}",
         lobby_id.raw()
     );
-    println!("[{}] Lobby code: {}", TAG, config.lobby_code);
+    debug!(target: TAG, "Lobby code: {}", config.lobby_code);
 
     // Always joinable
     matchmaking.set_lobby_joinable(lobby_id, true);
@@ -276,18 +387,9 @@ fn set_lobby_data(
         if config.unlisted { "false" } else { "true" },
     );
     // This is a CSV of SteamIDs
-    matchmaking.set_lobby_data(
-        lobby_id,
-        "banned_players",
-        &config
-            .ban_list
-            .iter()
-            .map(|id| id.to_string())
-            .collect::<Vec<String>>()
-            .join(","),
-    );
+    matchmaking.set_lobby_data(lobby_id, "banned_players", &server.ban_list.to_lobby_data());
     matchmaking.set_lobby_data(lobby_id, "cap", config.max_players.to_string().as_str());
-    matchmaking.set_lobby_data(lobby_id, "count", user_count.to_string().as_str());
+    matchmaking.set_lobby_data(lobby_id, "count", server.players.count().to_string().as_str());
     matchmaking.set_lobby_data(lobby_id, "server_browser_value", "0");
     matchmaking.set_lobby_data(lobby_id, "lurefilter", "dedicated");
 
@@ -303,9 +405,9 @@ fn on_lobby_chat_update(server: &mut Server, game: &mut Game, update: LobbyChatU
     {
         return;
     }
-    println!(
-        "[{}] Lobby update: user_changed = {}, change = {:?}, making_change = {}",
-        TAG,
+    debug!(
+        target: TAG,
+        "Lobby update: user_changed = {}, change = {:?}, making_change = {}",
         update.user_changed.raw(),
         update.member_state_change,
         update.making_change.raw(),
@@ -317,64 +419,63 @@ fn on_lobby_chat_update(server: &mut Server, game: &mut Game, update: LobbyChatU
     {
         game.actor_manager
             .remove_all_actors_by_creator(&update.user_changed);
-        server.users.remove(&update.making_change.raw());
+        server.chat_filter.forget(&update.user_changed);
+        server.record_event(AuditEvent::Leave {
+            steam_id: update.user_changed.raw(),
+            name: server.persona_name(&update.user_changed),
+        });
+        // Kicked and banned players were already removed and announced.
+        if let Some(player) = server.players.remove(&update.user_changed) {
+            player::announce_presence(server, Presence::Leave, &player.name, false);
+        }
+        // Votes of players who left no longer count.
+        moderation::check_vote_kick(server, game);
         // We don't close any sessions here since the rust bindings doesn't expose a way to do this.
         // The session should timeout anyway after a few minutes.
     } else if update.member_state_change == ChatMemberStateChange::Entered {
         if server.banned_steam_id(&update.user_changed) {
-            println!(
-                "[{}] Sending force_disconnect_player packet to block P2P on players: steam_id = {}",
-                TAG,
-                update.user_changed.raw()
-            );
-
-            send_variant_p2p(
-                &server.sender_p2p_packet,
-                build_force_disconnect_player_packet(&update.user_changed.raw()),
-                P2pPacketTarget::All,
-                P2pChannel::GameState,
-                SendType::Reliable,
-            );
+            moderation::force_disconnect_player(server, &update.user_changed);
             return;
         }
+        server.record_event(AuditEvent::Join {
+            steam_id: update.user_changed.raw(),
+            name: server.persona_name(&update.user_changed),
+        });
     }
 }
 
 fn on_lobby_chat_msg(server: &mut Server, msg: LobbyChatMsg) {
     let steam_id_u64 = msg.user.raw();
     let lobby_id = msg.lobby;
-    println!("[{}] Lobby message: steam_id = {}", TAG, steam_id_u64);
+    debug!(target: TAG, "Lobby message: steam_id = {}", steam_id_u64);
     let mut buffer = [0u8; 1024];
     server
-        .steam_client
+        .steam()
         .matchmaking()
         .get_lobby_chat_entry(lobby_id, msg.chat_id, &mut buffer);
     let chat_text = String::from_utf8_lossy(&buffer).into_owned();
-    println!(
-        "[{}] Lobby message from {}: {}",
-        TAG, steam_id_u64, chat_text
-    );
+    debug!(target: TAG, "Lobby message from {}: {}", steam_id_u64, chat_text);
     if chat_text.trim_matches(char::from(0)) == "$weblobby_join_request" {
         if server.ban_list.contains(&steam_id_u64) {
             let msg = format!("$weblobby_request_denied_deny-{}", steam_id_u64);
             let _ = server
-                .steam_client
+                .steam()
                 .matchmaking()
                 .send_lobby_chat_message(lobby_id, msg.as_bytes());
             return;
         }
-        if server.users.len() as u32 >= server.config.max_players {
+        if server.players.count() as u32 >= server.config.max_players {
             let msg = format!("$weblobby_request_denied_full-{}", steam_id_u64);
             let _ = server
-                .steam_client
+                .steam()
                 .matchmaking()
                 .send_lobby_chat_message(lobby_id, msg.as_bytes());
             return;
         }
-        server.users.insert(steam_id_u64);
+        server.insert_player(msg.user);
         let msg = format!("$weblobby_request_accepted-{}", steam_id_u64);
         let _ = server
-            .steam_client
+            .steam()
             .matchmaking()
             .send_lobby_chat_message(lobby_id, msg.as_bytes());
         send_variant_p2p(
@@ -388,27 +489,27 @@ fn on_lobby_chat_msg(server: &mut Server, msg: LobbyChatMsg) {
 }
 
 fn on_net_session_request(server: &mut Server, request: SessionRequest<ClientManager>) {
-    println!("[{}] Session request received...", TAG);
+    debug!(target: TAG, "Session request received...");
     let steam_id = request.remote().steam_id();
     let Some(steam_id) = steam_id else {
         request.reject();
         return;
     };
-    println!("[{}] Session request: steam_id = {}", TAG, steam_id.raw());
+    info!(target: TAG, "Session request: steam_id = {}", steam_id.raw());
     // Check for reasons to not accept the request.
     if server.banned_steam_id(&steam_id) {
-        println!(
-            "[{}] Blocking session request from user on ban list: steam_id = {}",
-            TAG,
+        info!(
+            target: TAG,
+            "Blocking session request from user on ban list: steam_id = {}",
             steam_id.raw()
         );
         request.reject();
         return;
     }
     // Checks have passed, let's accept the request
-    println!(
-        "[{}] Accepting session request: steam_id = {}",
-        TAG,
+    info!(
+        target: TAG,
+        "Accepting session request: steam_id = {}",
         steam_id.raw()
     );
     request.accept();
@@ -416,7 +517,7 @@ fn on_net_session_request(server: &mut Server, request: SessionRequest<ClientMan
     // Send the handshake
     send_variant_p2p(
         &server.sender_p2p_packet,
-        build_handshake_packet(server.steam_client.user().steam_id()),
+        build_handshake_packet(server.host_steam_id),
         P2pPacketTarget::All,
         P2pChannel::GameState,
         SendType::Reliable,
@@ -0,0 +1,315 @@
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+
+use steamworks::SteamId;
+
+use crate::{game::actor::Actor, packet::P2pChannel};
+
+/// Upper bounds of the tick duration histogram buckets, in seconds. A tick is 62.5ms.
+static TICK_DURATION_BUCKETS: [f64; 9] =
+    [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.0625, 0.1, 0.25];
+
+#[derive(Default)]
+struct PacketCounter {
+    packets: u64,
+    bytes: u64,
+}
+
+struct Histogram {
+    buckets: Vec<(f64, u64)>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &[f64]) -> Self {
+        Self {
+            buckets: bounds.iter().map(|bound| (*bound, 0)).collect(),
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bound, count) in self.buckets.iter_mut() {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+/// Counters for the Prometheus `/metrics` endpoint. Gauges such as the number of users and actors
+/// are read from the game state when rendering instead.
+pub struct Metrics {
+    /// Keyed by channel and packet type. Bytes are compressed, as sent over the network.
+    packets_received: BTreeMap<(&'static str, String), PacketCounter>,
+    packets_sent: BTreeMap<(&'static str, String), PacketCounter>,
+    decode_failures: u64,
+    /// Keyed by the reason the packet was dropped.
+    packets_rejected: BTreeMap<&'static str, u64>,
+    tick_duration: Histogram,
+    tick_overruns: u64,
+    lobby_data_refreshes: u64,
+    /// Host spawns and despawns keyed by actor type.
+    spawns: BTreeMap<String, u64>,
+    despawns: BTreeMap<String, u64>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            packets_received: BTreeMap::new(),
+            packets_sent: BTreeMap::new(),
+            decode_failures: 0,
+            packets_rejected: BTreeMap::new(),
+            tick_duration: Histogram::new(&TICK_DURATION_BUCKETS),
+            tick_overruns: 0,
+            lobby_data_refreshes: 0,
+            spawns: BTreeMap::new(),
+            despawns: BTreeMap::new(),
+        }
+    }
+
+    pub fn record_packet_received(&mut self, channel: P2pChannel, packet_type: &str, bytes: usize) {
+        let counter = self
+            .packets_received
+            .entry((channel.into(), packet_type.to_owned()))
+            .or_default();
+        counter.packets += 1;
+        counter.bytes += bytes as u64;
+    }
+
+    pub fn record_packet_sent(&mut self, channel: P2pChannel, packet_type: &str, bytes: usize) {
+        let counter = self
+            .packets_sent
+            .entry((channel.into(), packet_type.to_owned()))
+            .or_default();
+        counter.packets += 1;
+        counter.bytes += bytes as u64;
+    }
+
+    pub fn record_decode_failure(&mut self) {
+        self.decode_failures += 1;
+    }
+
+    pub fn record_packet_rejected(&mut self, reason: &'static str) {
+        *self.packets_rejected.entry(reason).or_default() += 1;
+    }
+
+    pub fn record_tick(&mut self, duration: Duration, tick_interval: Duration) {
+        self.tick_duration.observe(duration.as_secs_f64());
+        if duration > tick_interval {
+            self.tick_overruns += 1;
+        }
+    }
+
+    pub fn record_lobby_data_refresh(&mut self) {
+        self.lobby_data_refreshes += 1;
+    }
+
+    pub fn record_spawn(&mut self, actor_type: &str) {
+        *self.spawns.entry(actor_type.to_owned()).or_default() += 1;
+    }
+
+    pub fn record_despawn(&mut self, actor_type: &str) {
+        *self.despawns.entry(actor_type.to_owned()).or_default() += 1;
+    }
+
+    pub fn spawns(&self) -> &BTreeMap<String, u64> {
+        &self.spawns
+    }
+
+    pub fn despawns(&self) -> &BTreeMap<String, u64> {
+        &self.despawns
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self, users: usize, actors: &[&Actor], host_steam_id: &SteamId) -> String {
+        let mut out = String::new();
+
+        write_header(&mut out, "ducky_users", "gauge", "Users in the lobby.");
+        let _ = writeln!(out, "ducky_users {users}");
+
+        // (actor type, creator class) -> count
+        let mut actor_counts: BTreeMap<(String, &str), u64> = BTreeMap::new();
+        for actor in actors {
+            let creator = if actor.creator_id == *host_steam_id {
+                "host"
+            } else {
+                "user"
+            };
+            *actor_counts
+                .entry((String::from(actor.actor_type.clone()), creator))
+                .or_default() += 1;
+        }
+        write_header(
+            &mut out,
+            "ducky_actors",
+            "gauge",
+            "Actors by type and creator.",
+        );
+        for ((actor_type, creator), count) in actor_counts {
+            let _ = writeln!(
+                out,
+                "ducky_actors{{actor_type=\"{}\",creator=\"{creator}\"}} {count}",
+                escape_label(&actor_type)
+            );
+        }
+
+        write_packet_counters(&mut out, "received", &self.packets_received);
+        write_packet_counters(&mut out, "sent", &self.packets_sent);
+
+        write_header(
+            &mut out,
+            "ducky_packet_decode_failures_total",
+            "counter",
+            "Received packets which could not be decompressed or decoded.",
+        );
+        let _ = writeln!(
+            out,
+            "ducky_packet_decode_failures_total {}",
+            self.decode_failures
+        );
+
+        write_header(
+            &mut out,
+            "ducky_packets_rejected_total",
+            "counter",
+            "Received packets which were dropped, by reason.",
+        );
+        for (reason, count) in &self.packets_rejected {
+            let _ = writeln!(
+                out,
+                "ducky_packets_rejected_total{{reason=\"{reason}\"}} {count}"
+            );
+        }
+
+        write_header(
+            &mut out,
+            "ducky_tick_duration_seconds",
+            "histogram",
+            "Time spent on each tick, excluding sleep.",
+        );
+        for (bound, count) in &self.tick_duration.buckets {
+            let _ = writeln!(
+                out,
+                "ducky_tick_duration_seconds_bucket{{le=\"{bound}\"}} {count}"
+            );
+        }
+        let _ = writeln!(
+            out,
+            "ducky_tick_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+            self.tick_duration.count
+        );
+        let _ = writeln!(
+            out,
+            "ducky_tick_duration_seconds_sum {}",
+            self.tick_duration.sum
+        );
+        let _ = writeln!(
+            out,
+            "ducky_tick_duration_seconds_count {}",
+            self.tick_duration.count
+        );
+
+        write_header(
+            &mut out,
+            "ducky_tick_overruns_total",
+            "counter",
+            "Ticks which took longer than the tick interval.",
+        );
+        let _ = writeln!(out, "ducky_tick_overruns_total {}", self.tick_overruns);
+
+        write_header(
+            &mut out,
+            "ducky_lobby_data_refreshes_total",
+            "counter",
+            "Times the Steam lobby data was set.",
+        );
+        let _ = writeln!(
+            out,
+            "ducky_lobby_data_refreshes_total {}",
+            self.lobby_data_refreshes
+        );
+
+        write_header(
+            &mut out,
+            "ducky_actor_spawns_total",
+            "counter",
+            "Actors spawned by the host, by type.",
+        );
+        for (actor_type, count) in &self.spawns {
+            let _ = writeln!(
+                out,
+                "ducky_actor_spawns_total{{actor_type=\"{}\"}} {count}",
+                escape_label(actor_type)
+            );
+        }
+        write_header(
+            &mut out,
+            "ducky_actor_despawns_total",
+            "counter",
+            "Actors despawned by the host, by type.",
+        );
+        for (actor_type, count) in &self.despawns {
+            let _ = writeln!(
+                out,
+                "ducky_actor_despawns_total{{actor_type=\"{}\"}} {count}",
+                escape_label(actor_type)
+            );
+        }
+
+        out
+    }
+}
+
+fn write_header(out: &mut String, name: &str, metric_type: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {metric_type}");
+}
+
+fn write_packet_counters(
+    out: &mut String,
+    direction: &str,
+    counters: &BTreeMap<(&'static str, String), PacketCounter>,
+) {
+    let packets_name = format!("ducky_packets_{direction}_total");
+    let bytes_name = format!("ducky_packet_bytes_{direction}_total");
+    write_header(
+        out,
+        &packets_name,
+        "counter",
+        &format!("Packets {direction}, by channel and packet type."),
+    );
+    for ((channel, packet_type), counter) in counters {
+        let _ = writeln!(
+            out,
+            "{packets_name}{{channel=\"{channel}\",type=\"{}\"}} {}",
+            escape_label(packet_type),
+            counter.packets
+        );
+    }
+    write_header(
+        out,
+        &bytes_name,
+        "counter",
+        &format!("Compressed bytes {direction}, by channel and packet type."),
+    );
+    for ((channel, packet_type), counter) in counters {
+        let _ = writeln!(
+            out,
+            "{bytes_name}{{channel=\"{channel}\",type=\"{}\"}} {}",
+            escape_label(packet_type),
+            counter.bytes
+        );
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,429 @@
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use ban::Ban;
+use filter::{FilterAction, FilterViolation};
+use log::info;
+use mute::Mute;
+use steamworks::{SendType, SteamId};
+
+use crate::{
+    audit::AuditEvent,
+    game::Game,
+    packet::{
+        util::{
+            build_ban_packet, build_force_disconnect_player_packet, build_kick_packet,
+            build_peer_was_banned_packet, build_peer_was_kicked_packet, build_weblobby_packet,
+            send_variant_p2p,
+        },
+        P2pChannel, P2pPacketTarget,
+    },
+    player::{announce_presence, Presence},
+    server::Server,
+    time::system_time_since_unix_epoch_seconds,
+};
+
+pub mod ban;
+pub mod filter;
+pub mod mute;
+pub mod vote_kick;
+
+static TAG: &str = "moderation";
+
+/// Tells all peers to stop accepting P2P packets from the given user.
+pub fn force_disconnect_player(server: &Server, steam_id: &SteamId) {
+    info!(
+        target: TAG,
+        "Sending force_disconnect_player packet to block P2P on players: steam_id = {}",
+        steam_id.raw()
+    );
+    send_variant_p2p(
+        &server.sender_p2p_packet,
+        build_force_disconnect_player_packet(&steam_id.raw()),
+        P2pPacketTarget::All,
+        P2pChannel::GameState,
+        SendType::Reliable,
+    );
+}
+
+/// Kicks a user from the lobby. The user is free to rejoin afterwards.
+pub fn kick_player(server: &mut Server, game: &mut Game, steam_id: &SteamId) {
+    info!(
+        target: TAG,
+        "Kicking user: steam_id = {}, name = {}",
+        steam_id.raw(),
+        server.persona_name(steam_id)
+    );
+    send_variant_p2p(
+        &server.sender_p2p_packet,
+        build_kick_packet(),
+        P2pPacketTarget::SteamId(*steam_id),
+        P2pChannel::GameState,
+        SendType::Reliable,
+    );
+    send_variant_p2p(
+        &server.sender_p2p_packet,
+        build_peer_was_kicked_packet(steam_id.raw()),
+        P2pPacketTarget::All,
+        P2pChannel::GameState,
+        SendType::Reliable,
+    );
+    announce_presence(server, Presence::Kick, &server.persona_name(steam_id), false);
+    remove_from_lobby(server, game, steam_id);
+    server.record_event(AuditEvent::Kick {
+        steam_id: steam_id.raw(),
+        name: server.persona_name(steam_id),
+    });
+}
+
+/// Bans a user and removes them from the lobby if they are connected. A ban without a duration is
+/// permanent.
+pub fn ban_player(
+    server: &mut Server,
+    game: &mut Game,
+    steam_id: &SteamId,
+    reason: Option<String>,
+    duration: Option<Duration>,
+) {
+    info!(
+        target: TAG,
+        "Banning user: steam_id = {}, name = {}, reason = {reason:?}, duration = {duration:?}",
+        steam_id.raw(),
+        server.persona_name(steam_id)
+    );
+    let now = system_time_since_unix_epoch_seconds();
+    let expires_at = duration.map(|duration| now.saturating_add(duration.as_secs()));
+    server.record_event(AuditEvent::Ban {
+        steam_id: steam_id.raw(),
+        name: server.persona_name(steam_id),
+        reason: reason.clone(),
+        expires_at,
+    });
+    server.ban_list.insert(Ban {
+        steam_id: steam_id.raw(),
+        reason,
+        banned_at: now,
+        expires_at,
+    });
+    server.update_lobby_ban_list();
+
+    if server.players.contains(steam_id) {
+        send_variant_p2p(
+            &server.sender_p2p_packet,
+            build_ban_packet(),
+            P2pPacketTarget::SteamId(*steam_id),
+            P2pChannel::GameState,
+            SendType::Reliable,
+        );
+        send_variant_p2p(
+            &server.sender_p2p_packet,
+            build_peer_was_banned_packet(steam_id.raw()),
+            P2pPacketTarget::All,
+            P2pChannel::GameState,
+            SendType::Reliable,
+        );
+        announce_presence(server, Presence::Ban, &server.persona_name(steam_id), false);
+        remove_from_lobby(server, game, steam_id);
+    }
+}
+
+/// Removes the ban for the given user. Returns the removed ban, if there was one.
+pub fn unban_player(server: &mut Server, steam_id: &SteamId) -> Option<Ban> {
+    let ban = server.ban_list.remove(&steam_id.raw());
+    if ban.is_some() {
+        info!(target: TAG, "Unbanned user: steam_id = {}", steam_id.raw());
+        server.record_event(AuditEvent::Unban {
+            steam_id: steam_id.raw(),
+        });
+        server.update_lobby_ban_list();
+    }
+
+    ban
+}
+
+/// Mutes a user, which stops the server from running their commands. A mute without a duration is
+/// permanent. If the user has been muted too often recently, they are tempbanned instead.
+pub fn mute_player(
+    server: &mut Server,
+    game: &mut Game,
+    steam_id: &SteamId,
+    reason: Option<String>,
+    duration: Option<Duration>,
+) {
+    info!(
+        target: TAG,
+        "Muting user: steam_id = {}, name = {}, reason = {reason:?}, duration = {duration:?}",
+        steam_id.raw(),
+        server.persona_name(steam_id)
+    );
+    let now = system_time_since_unix_epoch_seconds();
+    let expires_at = duration.map(|duration| now.saturating_add(duration.as_secs()));
+    server.record_event(AuditEvent::Mute {
+        steam_id: steam_id.raw(),
+        name: server.persona_name(steam_id),
+        reason: reason.clone(),
+        expires_at,
+    });
+    let offenses = server.mute_list.insert(Mute {
+        steam_id: steam_id.raw(),
+        reason: reason.clone(),
+        muted_at: now,
+        expires_at,
+    });
+
+    let escalation_offenses = server.config.mute_escalation_offenses as usize;
+    if escalation_offenses > 0 && offenses >= escalation_offenses {
+        info!(
+            target: TAG,
+            "Escalating mute to tempban: steam_id = {}, offenses = {offenses}",
+            steam_id.raw()
+        );
+        server.mute_list.clear_offenses(&steam_id.raw());
+        let duration = Duration::from_secs(server.config.mute_escalation_tempban_minutes * 60);
+        ban_player(server, game, steam_id, reason, Some(duration));
+        return;
+    }
+
+    server.send_chat_message(
+        steam_id,
+        "You have been muted. The server will ignore your commands.",
+    );
+    if server.config.mute_announce {
+        server.broadcast_chat_message(format!("{} has been muted.", steam_id.raw()).as_str());
+    }
+}
+
+/// Removes the mute for the given user. Returns the removed mute, if there was one.
+pub fn unmute_player(server: &mut Server, steam_id: &SteamId) -> Option<Mute> {
+    let mute = server.mute_list.remove(&steam_id.raw());
+    if mute.is_some() {
+        info!(target: TAG, "Unmuted user: steam_id = {}", steam_id.raw());
+        server.record_event(AuditEvent::Unmute {
+            steam_id: steam_id.raw(),
+        });
+        server.send_chat_message(steam_id, "You are no longer muted.");
+    }
+
+    mute
+}
+
+/// Carries out the action of a chat filter violation against the sender of the message.
+pub fn apply_filter_violation(
+    server: &mut Server,
+    game: &mut Game,
+    steam_id: &SteamId,
+    violation: &FilterViolation,
+    message: &str,
+) {
+    let rule: &str = violation.rule.into();
+    info!(
+        target: TAG,
+        "Chat filter violation: steam_id = {}, rule = {rule}, action = {:?}",
+        steam_id.raw(),
+        violation.action
+    );
+    server.record_event(AuditEvent::Filter {
+        steam_id: steam_id.raw(),
+        name: server.persona_name(steam_id),
+        rule: rule.to_owned(),
+        action: format!("{:?}", violation.action).to_lowercase(),
+        message: message.to_owned(),
+    });
+    let reason = Some(format!("Chat filter: {rule}"));
+    match violation.action {
+        FilterAction::Off | FilterAction::Log => (),
+        FilterAction::Warn => server.send_chat_message(steam_id, violation.rule.warning()),
+        // Don't count further messages from an already muted player as new offenses.
+        FilterAction::Mute if server.muted_steam_id(steam_id) => (),
+        FilterAction::Mute => {
+            server.send_chat_message(steam_id, violation.rule.warning());
+            let duration = server.chat_filter.mute_duration();
+            mute_player(server, game, steam_id, reason, duration);
+        }
+        FilterAction::Kick => kick_player(server, game, steam_id),
+        FilterAction::Ban => {
+            let duration = server.chat_filter.ban_duration();
+            ban_player(server, game, steam_id, reason, duration);
+        }
+    }
+}
+
+/// Starts a vote to kick the target. Admins cannot be vote kicked.
+pub fn start_vote_kick(
+    server: &mut Server,
+    game: &mut Game,
+    initiator: &SteamId,
+    target: &SteamId,
+    reason: Option<String>,
+) -> Result<(), String> {
+    let config = server.config.vote_kick.clone();
+    if !config.enabled {
+        return Err("Vote kicking is disabled.".to_owned());
+    }
+    if !server.players.contains(target) {
+        return Err(format!("Player is not in the lobby: {}", target.raw()));
+    }
+    if server.is_admin(target) {
+        return Err("You cannot vote kick an admin.".to_owned());
+    }
+    if target == initiator {
+        return Err("You cannot vote kick yourself.".to_owned());
+    }
+    if server.players.count().saturating_sub(1) < config.min_players {
+        return Err(format!(
+            "At least {} players are needed to start a vote.",
+            config.min_players
+        ));
+    }
+
+    let target_name = server.persona_name(target);
+    server.vote_kick.start(
+        &config,
+        *initiator,
+        *target,
+        target_name.clone(),
+        reason.clone(),
+        Instant::now(),
+    )?;
+    info!(
+        target: TAG,
+        "Vote kick started: initiator = {}, target = {}, reason = {reason:?}",
+        initiator.raw(),
+        target.raw()
+    );
+    record_vote_kick(server, initiator, target, &reason, "started");
+    let reason = reason.map(|r| format!(" ({r})")).unwrap_or_default();
+    server.broadcast_chat_message(&format!(
+        "{} started a vote to kick {target_name}{reason}. Type !yes or !no within {}s.",
+        server.persona_name(initiator),
+        config.duration_secs
+    ));
+    check_vote_kick(server, game);
+
+    Ok(())
+}
+
+/// Records a vote on the active vote kick, kicking the target if the vote passes.
+pub fn cast_vote_kick(
+    server: &mut Server,
+    game: &mut Game,
+    voter: &SteamId,
+    yes: bool,
+) -> Result<(), String> {
+    if *voter == server.host_steam_id {
+        return Err("The host cannot vote.".to_owned());
+    }
+    if !server.players.contains(voter) {
+        return Err("Only players in the lobby can vote.".to_owned());
+    }
+    server.vote_kick.vote(*voter, yes)?;
+    server.send_chat_message(voter, if yes { "Voted yes." } else { "Voted no." });
+    check_vote_kick(server, game);
+
+    Ok(())
+}
+
+/// Kicks the target of the active vote if enough players voted yes. Only players still in the
+/// lobby count, so this is also called when a player leaves. A target who left is handled by
+/// `on_update`.
+pub fn check_vote_kick(server: &mut Server, game: &mut Game) {
+    let Some(vote) = server.vote_kick.active() else {
+        return;
+    };
+    let host_steam_id = server.host_steam_id;
+    let target = vote.target;
+    if !server.players.contains(&target) {
+        return;
+    }
+    let initiator = vote.initiator;
+    let reason = vote.reason.clone();
+    let target_name = vote.target_name.clone();
+    let eligible_voters: HashSet<SteamId> = server
+        .players
+        .iter()
+        .map(|player| player.steam_id)
+        .filter(|steam_id| *steam_id != host_steam_id && *steam_id != target)
+        .collect();
+    if !vote.has_passed(&eligible_voters, server.config.vote_kick.quorum) {
+        return;
+    }
+
+    server
+        .vote_kick
+        .finish(&server.config.vote_kick, Instant::now());
+    info!(
+        target: TAG,
+        "Vote kick passed: initiator = {}, target = {}, reason = {reason:?}",
+        initiator.raw(),
+        target.raw()
+    );
+    record_vote_kick(server, &initiator, &target, &reason, "passed");
+    server.broadcast_chat_message(&format!("Vote passed. {target_name} was kicked."));
+    kick_player(server, game, &target);
+}
+
+/// Lifts expired tempbans and mutes.
+pub fn on_update(server: &mut Server) {
+    let now = system_time_since_unix_epoch_seconds();
+    let expired = server.ban_list.remove_expired(now);
+    for ban in &expired {
+        info!(target: TAG, "Tempban expired: steam_id = {}", ban.steam_id);
+    }
+    if !expired.is_empty() {
+        server.update_lobby_ban_list();
+    }
+
+    for mute in server.mute_list.remove_expired(now) {
+        info!(target: TAG, "Mute expired: steam_id = {}", mute.steam_id);
+        server.send_chat_message(&SteamId::from_raw(mute.steam_id), "You are no longer muted.");
+    }
+
+    let now = Instant::now();
+    if let Some(vote) = server.vote_kick.active() {
+        let (initiator, target, reason) = (vote.initiator, vote.target, vote.reason.clone());
+        let target_name = vote.target_name.clone();
+        if !server.players.contains(&target) {
+            server.vote_kick.finish(&server.config.vote_kick, now);
+            record_vote_kick(server, &initiator, &target, &reason, "cancelled");
+            server.broadcast_chat_message(&format!("Vote cancelled. {target_name} left."));
+        } else if server.vote_kick.is_expired(now) {
+            server.vote_kick.finish(&server.config.vote_kick, now);
+            info!(target: TAG, "Vote kick failed: target = {}", target.raw());
+            record_vote_kick(server, &initiator, &target, &reason, "failed");
+            server.broadcast_chat_message(&format!("Vote to kick {target_name} failed."));
+        }
+    }
+    server.vote_kick.clear_expired_cooldowns(now);
+}
+
+fn record_vote_kick(
+    server: &mut Server,
+    initiator: &SteamId,
+    target: &SteamId,
+    reason: &Option<String>,
+    outcome: &str,
+) {
+    server.record_event(AuditEvent::VoteKick {
+        initiator: initiator.raw(),
+        target: target.raw(),
+        reason: reason.clone(),
+        outcome: outcome.to_owned(),
+    });
+}
+
+/// Blocks the user on all peers, drops their actors and removes them from the web lobby.
+fn remove_from_lobby(server: &mut Server, game: &mut Game, steam_id: &SteamId) {
+    force_disconnect_player(server, steam_id);
+    // Peers drop the user's actors when they drop the user, so we only need to forget them.
+    game.actor_manager.remove_all_actors_by_creator(steam_id);
+    server.players.remove(steam_id);
+    send_variant_p2p(
+        &server.sender_p2p_packet,
+        build_weblobby_packet(&server.players.steam_ids()),
+        P2pPacketTarget::All,
+        P2pChannel::GameState,
+        SendType::Reliable,
+    );
+}
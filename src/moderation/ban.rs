@@ -0,0 +1,144 @@
+use std::collections::{HashMap, HashSet};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persist::{load_json, save_json},
+    time::system_time_since_unix_epoch_seconds,
+};
+
+static TAG: &str = "moderation::ban";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Ban {
+    /// The banned SteamId as raw u64.
+    pub steam_id: u64,
+    pub reason: Option<String>,
+    /// Unix timestamp in seconds of when the ban was issued.
+    pub banned_at: u64,
+    /// Unix timestamp in seconds of when the ban expires. Permanent bans have no expiry.
+    pub expires_at: Option<u64>,
+}
+
+impl Ban {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at
+            .map(|expires_at| now >= expires_at)
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct BanFile {
+    bans: Vec<Ban>,
+}
+
+/// A list of bans which is persisted to a JSON file. Every change made through `insert` or `remove`
+/// is written to disk immediately. The permanent bans from `config.ban_list` are kept apart and are
+/// never written to the file.
+pub struct BanList {
+    path: String,
+    bans: HashMap<u64, Ban>,
+    config_bans: HashSet<u64>,
+}
+
+impl BanList {
+    /// Loads the ban list from the given path, along with the bans from `config.ban_list`. If the
+    /// file doesn't exist, an empty ban list is returned which will create the file on the next
+    /// change. See `load_json` for files which can't be read.
+    pub fn load(path: &str, config_bans: &[u64]) -> Self {
+        let bans = load_json::<BanFile>(path, "ban file").bans;
+        info!(target: TAG, "Loaded {} bans: path = {path}", bans.len());
+
+        Self {
+            path: path.to_owned(),
+            bans: bans.into_iter().map(|ban| (ban.steam_id, ban)).collect(),
+            config_bans: config_bans.iter().copied().collect(),
+        }
+    }
+
+    /// Replaces the bans from `config.ban_list`.
+    pub fn set_config_bans(&mut self, steam_ids: &[u64]) {
+        self.config_bans = steam_ids.iter().copied().collect();
+    }
+
+    /// Returns if the given SteamId is banned through `config.ban_list`. These bans can only be
+    /// lifted by editing the config.
+    pub fn is_config_ban(&self, steam_id: &u64) -> bool {
+        self.config_bans.contains(steam_id)
+    }
+
+    /// Inserts or replaces a ban and saves the ban list.
+    pub fn insert(&mut self, ban: Ban) {
+        self.bans.insert(ban.steam_id, ban);
+        self.save();
+    }
+
+    /// Removes the ban for the given SteamId and saves the ban list.
+    pub fn remove(&mut self, steam_id: &u64) -> Option<Ban> {
+        let ban = self.bans.remove(steam_id);
+        if ban.is_some() {
+            self.save();
+        }
+
+        ban
+    }
+
+    /// Returns if the given SteamId has an active ban, from the file or from `config.ban_list`.
+    pub fn contains(&self, steam_id: &u64) -> bool {
+        self.is_config_ban(steam_id) || self.get(steam_id).is_some()
+    }
+
+    /// Gets the active ban for the given SteamId.
+    pub fn get(&self, steam_id: &u64) -> Option<&Ban> {
+        self.bans
+            .get(steam_id)
+            .filter(|ban| !ban.is_expired(system_time_since_unix_epoch_seconds()))
+    }
+
+    /// Returns the number of banned SteamIds, including expired bans which have not been removed
+    /// yet.
+    pub fn count(&self) -> usize {
+        self.config_bans
+            .iter()
+            .filter(|id| !self.bans.contains_key(id))
+            .count()
+            + self.bans.len()
+    }
+
+    /// Removes all expired bans and returns them. The ban list is only saved if a ban was removed.
+    pub fn remove_expired(&mut self, now: u64) -> Vec<Ban> {
+        let expired_ids: Vec<u64> = self
+            .bans
+            .values()
+            .filter(|ban| ban.is_expired(now))
+            .map(|ban| ban.steam_id)
+            .collect();
+        let expired: Vec<Ban> = expired_ids
+            .iter()
+            .filter_map(|id| self.bans.remove(id))
+            .collect();
+        if !expired.is_empty() {
+            self.save();
+        }
+
+        expired
+    }
+
+    /// Returns the banned SteamIds as CSV, which is the format of the `banned_players` lobby field.
+    pub fn to_lobby_data(&self) -> String {
+        let ids: HashSet<&u64> = self.bans.keys().chain(&self.config_bans).collect();
+        ids.into_iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    fn save(&self) {
+        let ban_file = BanFile {
+            bans: self.bans.values().cloned().collect(),
+        };
+        save_json(&self.path, "ban file", &ban_file);
+    }
+}
@@ -0,0 +1,367 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use log::warn;
+use regex::Regex;
+use serde::Deserialize;
+use steamworks::SteamId;
+
+use crate::config::ChatFilterConfig;
+
+static TAG: &str = "moderation::filter";
+/// Matches anything shaped like a domain, `label.tld` with a TLD of two or more letters, since a
+/// list of TLDs is easy to dodge. `links.allowed_domains` holds the exceptions.
+static LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:https?://|www\.)?((?:[a-z0-9-]+\.)+[a-z]{2,})\b").unwrap()
+});
+
+/// What to do when a message breaks a filter rule. Actions are ordered by severity.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    #[default]
+    Off,
+    Log,
+    Warn,
+    Mute,
+    Kick,
+    Ban,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilterRule {
+    Word,
+    Pattern,
+    Spam,
+    Caps,
+    Link,
+}
+
+impl FilterRule {
+    /// A short explanation which is sent to players who are warned.
+    pub fn warning(&self) -> &'static str {
+        match self {
+            FilterRule::Word => "Please watch your language.",
+            FilterRule::Pattern => "Your message is not allowed here.",
+            FilterRule::Spam => "Please don't spam the chat.",
+            FilterRule::Caps => "Please don't use excessive caps.",
+            FilterRule::Link => "Please don't post links.",
+        }
+    }
+}
+
+impl From<FilterRule> for &'static str {
+    fn from(value: FilterRule) -> Self {
+        match value {
+            FilterRule::Word => "word",
+            FilterRule::Pattern => "pattern",
+            FilterRule::Spam => "spam",
+            FilterRule::Caps => "caps",
+            FilterRule::Link => "link",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FilterViolation {
+    pub rule: FilterRule,
+    pub action: FilterAction,
+}
+
+/// Checks chat messages against the rules in the `[chat_filter]` config section. The filter keeps a
+/// short history of recent messages per player to detect spam.
+pub struct ChatFilter {
+    config: ChatFilterConfig,
+    words: Vec<String>,
+    patterns: Vec<Regex>,
+    history: HashMap<SteamId, VecDeque<(Instant, String)>>,
+}
+
+impl ChatFilter {
+    pub fn new(config: &ChatFilterConfig) -> Self {
+        let mut chat_filter = Self {
+            config: config.clone(),
+            words: vec![],
+            patterns: vec![],
+            history: HashMap::new(),
+        };
+        chat_filter.set_config(config);
+
+        chat_filter
+    }
+
+    /// Replaces the filter rules. Message history is kept.
+    pub fn set_config(&mut self, config: &ChatFilterConfig) {
+        self.config = config.clone();
+        self.words = config
+            .words
+            .list
+            .iter()
+            .map(|word| word.to_lowercase())
+            .collect();
+        self.patterns = config
+            .patterns
+            .list
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    warn!(
+                        target: TAG,
+                        "Ignoring invalid pattern: pattern = {pattern}, error = {e}"
+                    );
+                    None
+                }
+            })
+            .collect();
+    }
+
+    /// How long the `mute` action mutes for. `None` is permanent.
+    pub fn mute_duration(&self) -> Option<Duration> {
+        Some(self.config.mute_minutes)
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| Duration::from_secs(minutes * 60))
+    }
+
+    /// How long the `ban` action bans for. `None` is permanent.
+    pub fn ban_duration(&self) -> Option<Duration> {
+        Some(self.config.ban_minutes)
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| Duration::from_secs(minutes * 60))
+    }
+
+    /// Checks a message against all rules and records it in the sender's history. Returns the
+    /// violation with the most severe action, if any rule was broken.
+    pub fn check(
+        &mut self,
+        steam_id: &SteamId,
+        message: &str,
+        now: Instant,
+    ) -> Option<FilterViolation> {
+        let mut violations = vec![];
+        if self.config.words.action != FilterAction::Off && self.contains_word(message) {
+            violations.push(FilterViolation {
+                rule: FilterRule::Word,
+                action: self.config.words.action,
+            });
+        }
+        if self.config.patterns.action != FilterAction::Off
+            && self
+                .patterns
+                .iter()
+                .any(|pattern| pattern.is_match(message))
+        {
+            violations.push(FilterViolation {
+                rule: FilterRule::Pattern,
+                action: self.config.patterns.action,
+            });
+        }
+        if self.config.links.action != FilterAction::Off && self.contains_link(message) {
+            violations.push(FilterViolation {
+                rule: FilterRule::Link,
+                action: self.config.links.action,
+            });
+        }
+        if self.config.caps.action != FilterAction::Off && self.is_excessive_caps(message) {
+            violations.push(FilterViolation {
+                rule: FilterRule::Caps,
+                action: self.config.caps.action,
+            });
+        }
+        if self.record_and_check_spam(steam_id, message, now)
+            && self.config.spam.action != FilterAction::Off
+        {
+            violations.push(FilterViolation {
+                rule: FilterRule::Spam,
+                action: self.config.spam.action,
+            });
+        }
+
+        violations
+            .into_iter()
+            .max_by_key(|violation| violation.action)
+    }
+
+    /// Forgets the message history of a player, e.g. when they leave the lobby.
+    pub fn forget(&mut self, steam_id: &SteamId) {
+        self.history.remove(steam_id);
+    }
+
+    fn contains_word(&self, message: &str) -> bool {
+        let message = message.to_lowercase();
+        let message_words: Vec<&str> = message
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        self.words.iter().any(|word| {
+            if word.contains(' ') {
+                message.contains(word.as_str())
+            } else {
+                message_words.contains(&word.as_str())
+            }
+        })
+    }
+
+    fn contains_link(&self, message: &str) -> bool {
+        LINK_REGEX.captures_iter(message).any(|captures| {
+            let domain = captures[1].to_lowercase();
+            !self.config.links.allowed_domains.iter().any(|allowed| {
+                let allowed = allowed.to_lowercase();
+                domain == allowed || domain.ends_with(&format!(".{allowed}"))
+            })
+        })
+    }
+
+    fn is_excessive_caps(&self, message: &str) -> bool {
+        let letters = message.chars().filter(|c| c.is_alphabetic()).count();
+        if letters < self.config.caps.min_letters.max(1) {
+            return false;
+        }
+        let uppercase = message.chars().filter(|c| c.is_uppercase()).count();
+
+        uppercase as f64 / letters as f64 >= self.config.caps.ratio
+    }
+
+    /// Records the message in the sender's history and returns if they are repeating themselves or
+    /// sending messages too quickly.
+    fn record_and_check_spam(&mut self, steam_id: &SteamId, message: &str, now: Instant) -> bool {
+        let window = Duration::from_secs(self.config.spam.window_secs);
+        let normalized = message.trim().to_lowercase();
+        let history = self.history.entry(*steam_id).or_default();
+        while history
+            .front()
+            .map(|(sent_at, _)| now.duration_since(*sent_at) > window)
+            .unwrap_or(false)
+        {
+            history.pop_front();
+        }
+        history.push_back((now, normalized.clone()));
+
+        let repeats = history.iter().filter(|(_, m)| *m == normalized).count();
+        let spam_config = &self.config.spam;
+        (spam_config.repeat_count > 0 && repeats > spam_config.repeat_count)
+            || (spam_config.message_count > 0 && history.len() > spam_config.message_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use steamworks::SteamId;
+
+    use super::{ChatFilter, FilterAction, FilterRule};
+    use crate::config::ChatFilterConfig;
+
+    fn check(chat_filter: &mut ChatFilter, message: &str) -> Option<(FilterRule, FilterAction)> {
+        chat_filter
+            .check(&SteamId::from_raw(1), message, Instant::now())
+            .map(|violation| (violation.rule, violation.action))
+    }
+
+    #[test]
+    fn words_match_whole_words_ignoring_case() {
+        let mut config = ChatFilterConfig::default();
+        config.words.action = FilterAction::Warn;
+        config.words.list = vec!["Heck".to_owned(), "bad phrase".to_owned()];
+        let mut chat_filter = ChatFilter::new(&config);
+
+        assert_eq!(
+            check(&mut chat_filter, "what the HECK"),
+            Some((FilterRule::Word, FilterAction::Warn))
+        );
+        assert_eq!(
+            check(&mut chat_filter, "a bad phrase here"),
+            Some((FilterRule::Word, FilterAction::Warn))
+        );
+        assert_eq!(check(&mut chat_filter, "heckin good fish"), None);
+    }
+
+    #[test]
+    fn links_allow_listed_domains_and_subdomains() {
+        let mut config = ChatFilterConfig::default();
+        config.links.action = FilterAction::Mute;
+        config.links.allowed_domains = vec!["steamcommunity.com".to_owned()];
+        let mut chat_filter = ChatFilter::new(&config);
+
+        assert_eq!(
+            check(&mut chat_filter, "join example.xyz now"),
+            Some((FilterRule::Link, FilterAction::Mute))
+        );
+        assert_eq!(
+            check(&mut chat_filter, "https://steamcommunity.com/id/x"),
+            None
+        );
+        assert_eq!(check(&mut chat_filter, "www.help.steamcommunity.com"), None);
+    }
+
+    #[test]
+    fn links_match_any_tld() {
+        let mut config = ChatFilterConfig::default();
+        config.links.action = FilterAction::Warn;
+        let mut chat_filter = ChatFilter::new(&config);
+
+        for message in [
+            "shop.de",
+            "free-stuff.app/x",
+            "HTTPS://Grief.Dev",
+            "my.co.uk",
+        ] {
+            assert_eq!(
+                check(&mut chat_filter, message),
+                Some((FilterRule::Link, FilterAction::Warn)),
+                "{message}"
+            );
+        }
+        assert_eq!(check(&mut chat_filter, "caught a 1.5 kg fish"), None);
+    }
+
+    #[test]
+    fn caps_need_enough_letters() {
+        let mut config = ChatFilterConfig::default();
+        config.caps.action = FilterAction::Log;
+        let mut chat_filter = ChatFilter::new(&config);
+
+        assert_eq!(check(&mut chat_filter, "NICE FISH"), None);
+        assert_eq!(
+            check(&mut chat_filter, "WHAT A HUGE FISH THAT IS"),
+            Some((FilterRule::Caps, FilterAction::Log))
+        );
+    }
+
+    #[test]
+    fn spam_counts_repeats_within_the_window() {
+        let mut config = ChatFilterConfig::default();
+        config.spam.action = FilterAction::Kick;
+        let mut chat_filter = ChatFilter::new(&config);
+        let steam_id = SteamId::from_raw(1);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(chat_filter.check(&steam_id, "hello", now).is_none());
+        }
+        let violation = chat_filter.check(&steam_id, " Hello ", now).unwrap();
+        assert_eq!(violation.rule, FilterRule::Spam);
+
+        let later = now + Duration::from_secs(config.spam.window_secs + 1);
+        assert!(chat_filter.check(&steam_id, "hello", later).is_none());
+    }
+
+    #[test]
+    fn most_severe_violation_wins() {
+        let mut config = ChatFilterConfig::default();
+        config.words.action = FilterAction::Warn;
+        config.words.list = vec!["heck".to_owned()];
+        config.patterns.action = FilterAction::Ban;
+        config.patterns.list = vec![r"(?i)free\s+robux".to_owned(), "(".to_owned()];
+        let mut chat_filter = ChatFilter::new(&config);
+
+        assert_eq!(
+            check(&mut chat_filter, "heck, FREE robux"),
+            Some((FilterRule::Pattern, FilterAction::Ban))
+        );
+    }
+}
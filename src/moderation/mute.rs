@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persist::{load_json, save_json},
+    time::system_time_since_unix_epoch_seconds,
+};
+
+static TAG: &str = "moderation::mute";
+/// Offenses older than this many seconds no longer count towards escalation.
+static OFFENSE_WINDOW_SECS: u64 = 60 * 60 * 24;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Mute {
+    /// The muted SteamId as raw u64.
+    pub steam_id: u64,
+    pub reason: Option<String>,
+    /// Unix timestamp in seconds of when the mute was issued.
+    pub muted_at: u64,
+    /// Unix timestamp in seconds of when the mute expires. Permanent mutes have no expiry.
+    pub expires_at: Option<u64>,
+}
+
+impl Mute {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at
+            .map(|expires_at| now >= expires_at)
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct MuteFile {
+    mutes: Vec<Mute>,
+    /// Unix timestamps in seconds of past mutes, keyed by raw SteamId.
+    offenses: HashMap<u64, Vec<u64>>,
+}
+
+/// A list of mutes and past offenses which is persisted to a JSON file, similar to `BanList`.
+pub struct MuteList {
+    path: String,
+    mutes: HashMap<u64, Mute>,
+    offenses: HashMap<u64, Vec<u64>>,
+}
+
+impl MuteList {
+    /// Loads the mute list from the given path. If the file doesn't exist, an empty mute list is
+    /// returned which will create the file on the next change. See `load_json` for files which
+    /// can't be read.
+    pub fn load(path: &str) -> Self {
+        let mute_file: MuteFile = load_json(path, "mute file");
+        info!(
+            target: TAG,
+            "Loaded {} mutes: path = {path}",
+            mute_file.mutes.len()
+        );
+
+        Self {
+            path: path.to_owned(),
+            mutes: mute_file
+                .mutes
+                .into_iter()
+                .map(|mute| (mute.steam_id, mute))
+                .collect(),
+            offenses: mute_file.offenses,
+        }
+    }
+
+    /// Inserts or replaces a mute, records an offense and saves the mute list. Returns the number
+    /// of offenses within the offense window, including this one.
+    pub fn insert(&mut self, mute: Mute) -> usize {
+        let offenses = self.offenses.entry(mute.steam_id).or_default();
+        offenses.retain(|muted_at| mute.muted_at.saturating_sub(*muted_at) < OFFENSE_WINDOW_SECS);
+        offenses.push(mute.muted_at);
+        let offense_count = offenses.len();
+
+        self.mutes.insert(mute.steam_id, mute);
+        self.save();
+
+        offense_count
+    }
+
+    /// Removes the mute for the given SteamId and saves the mute list.
+    pub fn remove(&mut self, steam_id: &u64) -> Option<Mute> {
+        let mute = self.mutes.remove(steam_id);
+        if mute.is_some() {
+            self.save();
+        }
+
+        mute
+    }
+
+    /// Forgets the past offenses of the given SteamId and saves the mute list.
+    pub fn clear_offenses(&mut self, steam_id: &u64) {
+        if self.offenses.remove(steam_id).is_some() {
+            self.save();
+        }
+    }
+
+    /// Returns if the given SteamId has an active mute.
+    pub fn contains(&self, steam_id: &u64) -> bool {
+        self.get(steam_id).is_some()
+    }
+
+    /// Gets the active mute for the given SteamId.
+    pub fn get(&self, steam_id: &u64) -> Option<&Mute> {
+        self.mutes
+            .get(steam_id)
+            .filter(|mute| !mute.is_expired(system_time_since_unix_epoch_seconds()))
+    }
+
+    /// Returns the number of mutes, including expired mutes which have not been removed yet.
+    pub fn count(&self) -> usize {
+        self.mutes.len()
+    }
+
+    /// Removes all expired mutes and returns them. The mute list is only saved if a mute was
+    /// removed.
+    pub fn remove_expired(&mut self, now: u64) -> Vec<Mute> {
+        let expired_ids: Vec<u64> = self
+            .mutes
+            .values()
+            .filter(|mute| mute.is_expired(now))
+            .map(|mute| mute.steam_id)
+            .collect();
+        let expired: Vec<Mute> = expired_ids
+            .iter()
+            .filter_map(|id| self.mutes.remove(id))
+            .collect();
+        if !expired.is_empty() {
+            self.save();
+        }
+
+        expired
+    }
+
+    fn save(&self) {
+        let mute_file = MuteFile {
+            mutes: self.mutes.values().cloned().collect(),
+            offenses: self.offenses.clone(),
+        };
+        save_json(&self.path, "mute file", &mute_file);
+    }
+}
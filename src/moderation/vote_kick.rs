@@ -0,0 +1,274 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use steamworks::SteamId;
+
+use crate::config::VoteKickConfig;
+
+pub struct VoteKick {
+    pub target: SteamId,
+    /// The target's persona name when the vote started, for messages sent after they left.
+    pub target_name: String,
+    pub initiator: SteamId,
+    pub reason: Option<String>,
+    pub yes: HashSet<SteamId>,
+    pub no: HashSet<SteamId>,
+    pub expires_at: Instant,
+}
+
+impl VoteKick {
+    /// Returns if enough players voted yes. Only votes by `eligible_voters` are counted, so votes
+    /// from players who left since don't.
+    pub fn has_passed(&self, eligible_voters: &HashSet<SteamId>, quorum: f64) -> bool {
+        let required = ((eligible_voters.len() as f64 * quorum).ceil() as usize).max(1);
+        let yes = self.yes.intersection(eligible_voters).count();
+        let no = self.no.intersection(eligible_voters).count();
+        yes >= required && yes > no
+    }
+}
+
+/// Keeps track of the active vote kick and the cooldowns for starting new ones. Only one vote can
+/// run at a time.
+pub struct VoteKickManager {
+    active: Option<VoteKick>,
+    initiator_cooldowns: HashMap<SteamId, Instant>,
+    target_cooldowns: HashMap<SteamId, Instant>,
+}
+
+impl VoteKickManager {
+    pub fn new() -> Self {
+        Self {
+            active: None,
+            initiator_cooldowns: HashMap::new(),
+            target_cooldowns: HashMap::new(),
+        }
+    }
+
+    pub fn active(&self) -> Option<&VoteKick> {
+        self.active.as_ref()
+    }
+
+    /// Starts a vote against the given target. The initiator automatically votes yes.
+    pub fn start(
+        &mut self,
+        config: &VoteKickConfig,
+        initiator: SteamId,
+        target: SteamId,
+        target_name: String,
+        reason: Option<String>,
+        now: Instant,
+    ) -> Result<&VoteKick, String> {
+        if self.active.is_some() {
+            return Err("A vote is already running.".to_owned());
+        }
+        if let Some(cooldown) = self
+            .initiator_cooldowns
+            .get(&initiator)
+            .filter(|cooldown| now < **cooldown)
+        {
+            return Err(format!(
+                "You can start another vote in {}s.",
+                cooldown.duration_since(now).as_secs()
+            ));
+        }
+        if self
+            .target_cooldowns
+            .get(&target)
+            .is_some_and(|cooldown| now < *cooldown)
+        {
+            return Err("A vote against this player was held recently.".to_owned());
+        }
+
+        self.initiator_cooldowns.insert(
+            initiator,
+            now + Duration::from_secs(config.initiator_cooldown_secs),
+        );
+        Ok(self.active.insert(VoteKick {
+            target,
+            target_name,
+            initiator,
+            reason,
+            yes: HashSet::from([initiator]),
+            no: HashSet::new(),
+            expires_at: now + Duration::from_secs(config.duration_secs),
+        }))
+    }
+
+    /// Records a vote on the active vote. A player may change their vote.
+    pub fn vote(&mut self, voter: SteamId, yes: bool) -> Result<&VoteKick, String> {
+        let Some(vote) = self.active.as_mut() else {
+            return Err("There is no vote running.".to_owned());
+        };
+        if voter == vote.target {
+            return Err("You cannot vote on your own kick.".to_owned());
+        }
+        if yes {
+            vote.no.remove(&voter);
+            vote.yes.insert(voter);
+        } else {
+            vote.yes.remove(&voter);
+            vote.no.insert(voter);
+        }
+
+        Ok(vote)
+    }
+
+    /// Ends the active vote, starting the cooldown for its target.
+    pub fn finish(&mut self, config: &VoteKickConfig, now: Instant) -> Option<VoteKick> {
+        let vote = self.active.take()?;
+        self.target_cooldowns.insert(
+            vote.target,
+            now + Duration::from_secs(config.target_cooldown_secs),
+        );
+
+        Some(vote)
+    }
+
+    /// Returns if the active vote ran out of time.
+    pub fn is_expired(&self, now: Instant) -> bool {
+        self.active
+            .as_ref()
+            .map(|vote| now >= vote.expires_at)
+            .unwrap_or(false)
+    }
+
+    /// Drops cooldowns which have passed.
+    pub fn clear_expired_cooldowns(&mut self, now: Instant) {
+        self.initiator_cooldowns.retain(|_, cooldown| now < *cooldown);
+        self.target_cooldowns.retain(|_, cooldown| now < *cooldown);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        time::{Duration, Instant},
+    };
+
+    use steamworks::SteamId;
+
+    use super::VoteKickManager;
+    use crate::config::VoteKickConfig;
+
+    fn steam_id(raw: u64) -> SteamId {
+        SteamId::from_raw(raw)
+    }
+
+    #[test]
+    fn quorum_needs_enough_yes_votes_and_a_majority() {
+        let mut vote_kick = VoteKickManager::new();
+        let config = VoteKickConfig::default();
+        let now = Instant::now();
+        vote_kick
+            .start(&config, steam_id(1), steam_id(9), String::new(), None, now)
+            .unwrap();
+        let eligible_voters = HashSet::from([steam_id(1), steam_id(2), steam_id(3), steam_id(4)]);
+
+        // Four eligible voters at a 0.5 quorum need two yes votes.
+        let vote = vote_kick.active().unwrap();
+        assert!(!vote.has_passed(&eligible_voters, config.quorum));
+        let vote = vote_kick.vote(steam_id(2), true).unwrap();
+        assert!(vote.has_passed(&eligible_voters, config.quorum));
+
+        vote_kick.vote(steam_id(3), false).unwrap();
+        let vote = vote_kick.vote(steam_id(4), false).unwrap();
+        assert!(!vote.has_passed(&eligible_voters, config.quorum));
+
+        // Changing a vote moves it to the other side.
+        let vote = vote_kick.vote(steam_id(4), true).unwrap();
+        assert_eq!((vote.yes.len(), vote.no.len()), (3, 1));
+        assert!(vote.has_passed(&eligible_voters, config.quorum));
+
+        assert!(vote_kick.vote(steam_id(9), false).is_err());
+    }
+
+    #[test]
+    fn votes_of_players_who_left_are_not_counted() {
+        let mut vote_kick = VoteKickManager::new();
+        let config = VoteKickConfig::default();
+        let now = Instant::now();
+        vote_kick
+            .start(&config, steam_id(1), steam_id(9), String::new(), None, now)
+            .unwrap();
+        let vote = vote_kick.vote(steam_id(2), true).unwrap();
+        let eligible_voters = HashSet::from([steam_id(1), steam_id(2), steam_id(3), steam_id(4)]);
+        assert!(vote.has_passed(&eligible_voters, config.quorum));
+
+        // Once the voters who said yes leave, their votes no longer count.
+        let eligible_voters = HashSet::from([steam_id(3), steam_id(4)]);
+        assert!(!vote.has_passed(&eligible_voters, config.quorum));
+    }
+
+    #[test]
+    fn initiator_and_target_cooldowns() {
+        let mut vote_kick = VoteKickManager::new();
+        let config = VoteKickConfig::default();
+        let now = Instant::now();
+        vote_kick
+            .start(&config, steam_id(1), steam_id(9), String::new(), None, now)
+            .unwrap();
+        assert!(vote_kick
+            .start(&config, steam_id(2), steam_id(8), String::new(), None, now)
+            .is_err());
+        vote_kick.finish(&config, now);
+
+        // The initiator waits `initiator_cooldown_secs`, the target `target_cooldown_secs`.
+        assert!(vote_kick
+            .start(&config, steam_id(1), steam_id(8), String::new(), None, now)
+            .is_err());
+        assert!(vote_kick
+            .start(&config, steam_id(2), steam_id(9), String::new(), None, now)
+            .is_err());
+
+        let after_initiator_cooldown = now + Duration::from_secs(config.initiator_cooldown_secs);
+        assert!(vote_kick
+            .start(
+                &config,
+                steam_id(1),
+                steam_id(9),
+                String::new(),
+                None,
+                after_initiator_cooldown
+            )
+            .is_err());
+        assert!(vote_kick
+            .start(
+                &config,
+                steam_id(1),
+                steam_id(8),
+                String::new(),
+                None,
+                after_initiator_cooldown
+            )
+            .is_ok());
+        vote_kick.finish(&config, after_initiator_cooldown);
+
+        let after_target_cooldown = now + Duration::from_secs(config.target_cooldown_secs);
+        assert!(vote_kick
+            .start(
+                &config,
+                steam_id(2),
+                steam_id(9),
+                String::new(),
+                None,
+                after_target_cooldown
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn votes_expire() {
+        let mut vote_kick = VoteKickManager::new();
+        let config = VoteKickConfig::default();
+        let now = Instant::now();
+        vote_kick
+            .start(&config, steam_id(1), steam_id(9), String::new(), None, now)
+            .unwrap();
+
+        assert!(!vote_kick.is_expired(now));
+        assert!(vote_kick.is_expired(now + Duration::from_secs(config.duration_secs)));
+    }
+}
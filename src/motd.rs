@@ -0,0 +1,88 @@
+use std::time::{Duration, Instant};
+
+use steamworks::SteamId;
+
+use crate::{player::Role, server::Server, template::TemplateContext};
+
+/// A chat message waiting to be sent to a player.
+struct QueuedMessage {
+    send_at: Instant,
+    steam_id: SteamId,
+    message: String,
+}
+
+/// Chat messages which are sent to players after a delay, such as the later parts of the MOTD.
+pub struct MessageQueue {
+    messages: Vec<QueuedMessage>,
+}
+
+impl MessageQueue {
+    pub fn new() -> Self {
+        Self { messages: vec![] }
+    }
+
+    pub fn push(&mut self, send_at: Instant, steam_id: SteamId, message: String) {
+        self.messages.push(QueuedMessage {
+            send_at,
+            steam_id,
+            message,
+        });
+    }
+
+    /// Removes and returns the messages which are due, in the order they were queued.
+    fn take_due(&mut self, now: Instant) -> Vec<QueuedMessage> {
+        let (due, pending) = std::mem::take(&mut self.messages)
+            .into_iter()
+            .partition(|message| message.send_at <= now);
+        self.messages = pending;
+
+        due
+    }
+}
+
+/// Sends the MOTD to a player who just joined. The first message is sent right away and the rest
+/// are queued `motd.delay_ms` apart.
+pub fn send_motd(server: &mut Server, steam_id: &SteamId) {
+    let (name, role, returning) = match server.players.get(steam_id) {
+        Some(player) => (player.name.clone(), player.role, player.is_returning()),
+        None => (server.persona_name(steam_id), Role::Player, false),
+    };
+    let config = &server.config.motd;
+    // The most specific list which has any messages wins.
+    let visit_messages = if returning {
+        &config.returning
+    } else {
+        &config.first_time
+    };
+    let templates = [config.roles.get(&role), Some(visit_messages)]
+        .into_iter()
+        .flatten()
+        .find(|messages| !messages.is_empty())
+        .unwrap_or(&config.messages);
+
+    let context = TemplateContext::new(server).with_name(&name);
+    let messages: Vec<String> = templates
+        .iter()
+        .map(|template| context.render(template))
+        .collect();
+    let delay = Duration::from_millis(config.delay_ms);
+    let now = Instant::now();
+    for (i, message) in messages.into_iter().enumerate() {
+        if i == 0 {
+            server.send_chat_message(steam_id, &message);
+        } else {
+            server
+                .message_queue
+                .push(now + delay * i as u32, *steam_id, message);
+        }
+    }
+}
+
+/// Sends queued messages which are due. Messages for players who have left are dropped.
+pub fn on_update(server: &mut Server) {
+    for message in server.message_queue.take_due(Instant::now()) {
+        if server.players.contains(&message.steam_id) {
+            server.send_chat_message(&message.steam_id, &message.message);
+        }
+    }
+}
@@ -1,13 +1,14 @@
-use std::io::Read;
+use std::{io::Read, time::Instant};
 
 use decode::decode_variant;
 use flate2::{bufread::GzEncoder, read::GzDecoder, Compression};
 use handler::resolve_handler;
+use log::{debug, error};
 use steamworks::{
     networking_types::{NetworkingIdentity, SendFlags},
     SendType, SteamId,
 };
-use variant::VariantValue;
+use variant::{Dictionary, VariantValue};
 
 use crate::{game::Game, Server};
 
@@ -51,8 +52,24 @@ impl P2pChannel {
     ];
 }
 
+impl From<P2pChannel> for &'static str {
+    fn from(value: P2pChannel) -> Self {
+        match value {
+            P2pChannel::ActorUpdate => "actor_update",
+            P2pChannel::ActorAction => "actor_action",
+            P2pChannel::GameState => "game_state",
+            P2pChannel::Chalk => "chalk",
+            P2pChannel::Guitar => "guitar",
+            P2pChannel::ActorAnimation => "actor_animation",
+            P2pChannel::Speech => "speech",
+        }
+    }
+}
+
 pub struct OutgoingP2pPacketRequest {
     pub data: Vec<u8>,
+    /// The `type` field of the packet, used for metrics.
+    pub packet_type: String,
     pub target: P2pPacketTarget,
     pub channel: P2pChannel,
     pub send_type: SendType,
@@ -74,60 +91,85 @@ pub fn on_receive_packet(
     game: &mut Game,
     buffer_vec: Vec<u8>,
     remote: SteamId,
+    channel: P2pChannel,
 ) {
-    // TODO: check if sender is banned
+    if server.banned_steam_id(&remote) {
+        server.metrics.record_packet_rejected("banned");
+        return;
+    }
+    server.players.record_packet(&remote, Instant::now());
     let mut d: GzDecoder<&[u8]> = GzDecoder::new(buffer_vec.as_slice());
     let mut decompressed_buf: Vec<u8> = vec![];
     if let Err(e) = d.read_to_end(&mut decompressed_buf) {
-        println!("[{TAG}] Error decompressing packet: {e}");
+        debug!(target: TAG, "Error decompressing packet: {e}");
+        server.metrics.record_decode_failure();
         return;
     }
     let var = decode_variant(&decompressed_buf);
     if let Ok(VariantValue::Dictionary(dict)) = var {
         if let Some(handler) = resolve_handler(&dict) {
+            let packet_type = get_packet_type(&dict);
+            server
+                .metrics
+                .record_packet_received(channel, &packet_type, buffer_vec.len());
             handler(server, game, remote, dict);
         } else {
-            println!("Unknown type for packet: root = {:?}", dict);
+            debug!(target: TAG, "Unknown type for packet: root = {:?}", dict);
+            server
+                .metrics
+                .record_packet_received(channel, "unknown", buffer_vec.len());
+            server.metrics.record_packet_rejected("unknown_type");
         }
     } else {
-        println!("Ignoring decode error for: buf = {:?}", decompressed_buf);
+        debug!(target: TAG, "Ignoring decode error for: buf = {:?}", decompressed_buf);
+        server.metrics.record_decode_failure();
+    }
+}
+
+/// Returns the `type` field of a packet, or `unknown` if it has none.
+pub fn get_packet_type(root: &Dictionary) -> String {
+    match root.get("type") {
+        Some(VariantValue::String(packet_type)) => packet_type.clone(),
+        _ => "unknown".to_owned(),
     }
 }
 
-pub fn on_send_packet(server: &Server, outgoing: OutgoingP2pPacketRequest) {
+pub fn on_send_packet(server: &mut Server, outgoing: OutgoingP2pPacketRequest) {
     let channel_u32 = outgoing.channel as u32;
     let mut e: GzEncoder<&[u8]> = GzEncoder::new(outgoing.data.as_slice(), Compression::fast());
     let mut buffer = Vec::new();
     if let Err(e) = e.read_to_end(&mut buffer) {
-        println!("[{TAG}] Error compressing packet: {e}");
+        error!(target: TAG, "Error compressing packet: {e}");
         return;
     }
 
     if let P2pPacketTarget::SteamId(steam_id) = outgoing.target {
-        let _ = server
-            .steam_client
-            .networking_messages()
-            .send_message_to_user(
-                NetworkingIdentity::new_steam_id(steam_id),
-                outgoing.get_send_flags(),
-                &buffer,
-                channel_u32,
-            );
+        let _ = server.steam().networking_messages().send_message_to_user(
+            NetworkingIdentity::new_steam_id(steam_id),
+            outgoing.get_send_flags(),
+            &buffer,
+            channel_u32,
+        );
+        server
+            .metrics
+            .record_packet_sent(outgoing.channel, &outgoing.packet_type, buffer.len());
     } else if let Some(lobby_id) = server.lobby_id {
         if let P2pPacketTarget::All = outgoing.target {
-            for steam_id in server.steam_client.matchmaking().lobby_members(lobby_id) {
-                if steam_id == server.steam_client.user().steam_id() {
+            for steam_id in server.steam().matchmaking().lobby_members(lobby_id) {
+                if steam_id == server.host_steam_id {
                     continue;
                 }
-                let _ = server
-                    .steam_client
-                    .networking_messages()
-                    .send_message_to_user(
-                        NetworkingIdentity::new_steam_id(steam_id),
-                        outgoing.get_send_flags(),
-                        &buffer,
-                        channel_u32,
-                    );
+                let _ = server.steam().networking_messages().send_message_to_user(
+                    NetworkingIdentity::new_steam_id(steam_id),
+                    outgoing.get_send_flags(),
+                    &buffer,
+                    channel_u32,
+                );
+                server.metrics.record_packet_sent(
+                    outgoing.channel,
+                    &outgoing.packet_type,
+                    buffer.len(),
+                );
             }
         }
     }
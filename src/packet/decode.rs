@@ -3,6 +3,7 @@ use std::{
     string::FromUtf8Error,
 };
 
+use log::debug;
 use super::variant::*;
 
 static TAG: &str = "decode";
@@ -51,7 +52,7 @@ fn read_variant(cursor: &mut Cursor<&[u8]>) -> io::Result<VariantValue> {
         Ok(18) => Ok(VariantValue::Dictionary(read_dictionary(cursor)?)),
         Ok(19) => Ok(VariantValue::Array(read_array(cursor)?)),
         invalid => {
-            println!("Invalid variant type: {:?}", invalid);
+            debug!(target: TAG, "Invalid variant type: {:?}", invalid);
             Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 "Invalid variant type",
@@ -130,7 +131,7 @@ fn read_dictionary(cursor: &mut Cursor<&[u8]>) -> io::Result<Dictionary> {
         {
             dict.insert(key_string, value_variant);
         } else {
-            println!("[{}] Got non-String Dictionary key", TAG);
+            debug!(target: TAG, "Got non-String Dictionary key");
         }
     }
 
@@ -13,6 +13,7 @@ pub mod message;
 pub mod new_player_join;
 pub mod request_actors;
 pub mod request_ping;
+pub mod send_ping;
 
 /// Packet handlers are pure functions responsible for handling a single packet type. All packet
 /// handlers have the same function signature `fn(&mut Server, &mut Game, SteamId, Dictionary)`.
@@ -37,6 +38,7 @@ pub fn resolve_handler(
         VariantValue::String(str) if str == "new_player_join" => Some(new_player_join::handle),
         VariantValue::String(str) if str == "request_actors" => Some(request_actors::handle),
         VariantValue::String(str) if str == "request_ping" => Some(request_ping::handle),
+        VariantValue::String(str) if str == "send_ping" => Some(send_ping::handle),
         _ => None,
     }
 }
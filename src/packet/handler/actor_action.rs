@@ -1,5 +1,6 @@
 use std::collections::VecDeque;
 
+use log::debug;
 use steamworks::SteamId;
 
 use crate::{
@@ -21,13 +22,14 @@ pub fn handle(server: &mut Server, game: &mut Game, steam_id: SteamId, mut packe
         packet.remove("params"),
     )
     else {
-        println!("[{TAG}] Ignoring invalid actor_action packet: packet = {packet:?}");
+        debug!(target: TAG, "Ignoring invalid actor_action packet: packet = {packet:?}");
         return;
     };
 
     let Some(action_fn) = resolve_action_handler(action.as_str()) else {
-        println!(
-            "[{TAG}] Ignoring actor_action packet without action handler: packet = {packet:?}"
+        debug!(
+            target: TAG,
+            "Ignoring actor_action packet without action handler: packet = {packet:?}"
         );
         return;
     };
@@ -70,7 +72,7 @@ fn wipe_actor(
     params: Array,
 ) {
     let Some(VariantValue::Int(target_id)) = params.get(0) else {
-        println!("[{TAG}] Ignoring invalid _wipe_actor packet: params = {params:?}");
+        debug!(target: TAG, "Ignoring invalid _wipe_actor packet: params = {params:?}");
         return;
     };
 
@@ -81,7 +83,7 @@ fn wipe_actor(
     let actor_manager = &mut game.actor_manager;
 
     let Some(host_actor) = actor_manager.get_actor(target_id).and_then(|actor| {
-        if actor.creator_id == server.steam_client.user().steam_id() {
+        if actor.creator_id == server.host_steam_id {
             Some(actor.id)
         } else {
             None
@@ -94,7 +96,7 @@ fn wipe_actor(
     // TODO: Check if the player is able to wipe this actor.
     actor_manager.despawn_host_actor(&server.sender_p2p_packet, &host_actor);
 
-    println!("[{TAG}] wipe_actor: id = {target_id}");
+    debug!(target: TAG, "wipe_actor: id = {target_id}");
 }
 
 fn set_zone(
@@ -108,13 +110,13 @@ fn set_zone(
     let (Some(VariantValue::String(zone)), Some(VariantValue::Int(zone_owner))) =
         (params.pop_front(), params.pop_front())
     else {
-        println!("[{TAG}] Ignoring invalid _set_zone packet: params = {params:?}");
+        debug!(target: TAG, "Ignoring invalid _set_zone packet: params = {params:?}");
         return;
     };
 
     let actor_manager = &mut game.actor_manager;
 
-    println!("[{TAG}] set_zone: id = {actor_id}, zone = {zone}, zone_owner = {zone_owner}");
+    debug!(target: TAG, "set_zone: id = {actor_id}, zone = {zone}, zone_owner = {zone_owner}");
 
     let owned_by_steam_id = actor_manager
         .get_actor(&actor_id)
@@ -122,13 +124,14 @@ fn set_zone(
         .unwrap_or(false);
     if !owned_by_steam_id {
         // User doesn't own the target actor, we can ignore.
-        println!(
-            "[{TAG}] Ignoring _set_zone packet for actor not owned by sender: actor_id = {actor_id:?}"
+        debug!(
+            target: TAG,
+            "Ignoring _set_zone packet for actor not owned by sender: actor_id = {actor_id:?}"
         );
         return;
     }
     let Some(_) = actor_manager.set_actor_zone(&actor_id, zone, zone_owner) else {
-        println!("[{TAG}] Failed _set_zone packet: actor_id = {actor_id}");
+        debug!(target: TAG, "Failed _set_zone packet: actor_id = {actor_id}");
         return;
     };
 }
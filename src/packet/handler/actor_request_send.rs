@@ -1,3 +1,4 @@
+use log::debug;
 use steamworks::SteamId;
 
 use crate::{
@@ -11,20 +12,25 @@ use crate::{
 
 static TAG: &str = "actor_request_send";
 
-pub fn handle(_server: &mut Server, game: &mut Game, steam_id: SteamId, packet: Dictionary) {
+pub fn handle(server: &mut Server, game: &mut Game, steam_id: SteamId, packet: Dictionary) {
     let Some(VariantValue::Array(list)) = packet.get("list") else {
-        println!("[{TAG}] Missing list in actor_request_send: packet = {packet:?}");
+        debug!(target: TAG, "Missing list in actor_request_send: packet = {packet:?}");
         return;
     };
 
     list.iter().for_each(|d| {
         if let VariantValue::Dictionary(d) = d {
-            insert_actor_from_list(game, &steam_id, d);
+            insert_actor_from_list(server, game, &steam_id, d);
         }
     });
 }
 
-fn insert_actor_from_list(game: &mut Game, steam_id: &SteamId, actor_dict: &Dictionary) {
+fn insert_actor_from_list(
+    server: &mut Server,
+    game: &mut Game,
+    steam_id: &SteamId,
+    actor_dict: &Dictionary,
+) {
     /*
     Dictionary format:
     {
@@ -36,7 +42,7 @@ fn insert_actor_from_list(game: &mut Game, steam_id: &SteamId, actor_dict: &Dict
     let (Some(VariantValue::String(type_string)), Some(VariantValue::Int(id))) =
         (actor_dict.get("type"), actor_dict.get("id"))
     else {
-        println!("[{TAG}] Invalid actor: dict = {actor_dict:?}");
+        debug!(target: TAG, "Invalid actor: dict = {actor_dict:?}");
         return;
     };
     let actor_type = ActorType::from(type_string.as_str());
@@ -57,14 +63,30 @@ fn insert_actor_from_list(game: &mut Game, steam_id: &SteamId, actor_dict: &Dict
             z: 0.0,
         },
     };
-    if !game
-        .actor_manager
-        .user_can_create_actor(&steam_id, false, &actor.actor_type)
-    {
-        println!("[{TAG}] Blocked user actor replication: actor = {actor:?}");
+    let role = server.role(&steam_id);
+    if let Err(e) = game.actor_manager.user_can_create_actor(
+        &steam_id,
+        role,
+        &actor.actor_type,
+        &server.config.props,
+    ) {
+        debug!(
+            target: TAG,
+            "Blocked user actor replication: actor = {actor:?}, error = {e:?}"
+        );
+        server.metrics.record_packet_rejected("blocked_actor");
         return;
     }
     
-    println!("[{TAG}] Inserting actor: actor = {actor:?}");
-    game.actor_manager.insert_actor(actor);
+    debug!(target: TAG, "Inserting actor: actor = {actor:?}");
+    let (actor_id, is_player) = (actor.id, actor.actor_type == ActorType::Player);
+    if !game.actor_manager.insert_actor(actor) {
+        server.metrics.record_packet_rejected("actor_id_collision");
+        return;
+    }
+    if is_player {
+        if let Some(player) = server.players.get_mut(steam_id) {
+            player.actor_id = Some(actor_id);
+        }
+    }
 }
@@ -1,3 +1,4 @@
+use log::debug;
 use steamworks::SteamId;
 
 use crate::{
@@ -23,8 +24,9 @@ pub fn handle(_server: &mut Server, game: &mut Game, steam_id: SteamId, packet:
         Some(VariantValue::Vector3(rot)),
     ) = (packet.get("actor_id"), packet.get("pos"), packet.get("rot"))
     else {
-        println!(
-            "[{TAG}] Ignoring invalid actor_update packet: steam_id = {} packet = {:?}",
+        debug!(
+            target: TAG,
+            "Ignoring invalid actor_update packet: steam_id = {} packet = {:?}",
             steam_id.raw(),
             packet
         );
@@ -32,8 +34,9 @@ pub fn handle(_server: &mut Server, game: &mut Game, steam_id: SteamId, packet:
     };
     if let Some(actor) = game.actor_manager.get_actor_mut(&actor_id) {
         if actor.creator_id != steam_id {
-            println!(
-                "[{TAG}] Ignoring actor_update packet from {} for actor {} they do not own",
+            debug!(
+                target: TAG,
+                "Ignoring actor_update packet from {} for actor {} they do not own",
                 steam_id.raw(),
                 actor_id
             );
@@ -47,8 +50,9 @@ pub fn handle(_server: &mut Server, game: &mut Game, steam_id: SteamId, packet:
         actor.rotation.z = rot.z;
     } else {
         game.peer_manager.add_peer_need_update(steam_id);
-        println!(
-            "[{TAG}] Ignoring actor_update packet from {} for non-existent actor {}",
+        debug!(
+            target: TAG,
+            "Ignoring actor_update packet from {} for non-existent actor {}",
             steam_id.raw(),
             actor_id
         );
@@ -1,3 +1,4 @@
+use log::debug;
 use steamworks::{SendType, SteamId};
 
 use crate::{
@@ -13,14 +14,14 @@ use crate::{
 static TAG: &str = "handshake";
 
 pub fn handle(server: &mut Server, _game: &mut Game, steam_id: SteamId, _packet: Dictionary) {
-    println!(
-        "[{}] Received handshake from: steam_id = {}",
-        TAG,
+    debug!(
+        target: TAG,
+        "Received handshake from: steam_id = {}",
         steam_id.raw()
     );
     send_variant_p2p(
         &server.sender_p2p_packet,
-        build_weblobby_packet(&server.users),
+        build_weblobby_packet(&server.players.steam_ids()),
         P2pPacketTarget::All,
         P2pChannel::GameState,
         SendType::Reliable,
@@ -1,3 +1,4 @@
+use log::debug;
 use steamworks::SteamId;
 
 use crate::{
@@ -11,9 +12,9 @@ use crate::{
 
 static TAG: &str = "instance_actor";
 
-pub fn handle(_server: &mut Server, game: &mut Game, steam_id: SteamId, mut packet: Dictionary) {
+pub fn handle(server: &mut Server, game: &mut Game, steam_id: SteamId, mut packet: Dictionary) {
     let Some(VariantValue::Dictionary(mut params)) = packet.remove("params") else {
-        println!("[{TAG}] Missing params in instance_actor packet.");
+        debug!(target: TAG, "Missing params in instance_actor packet.");
         return;
     };
     /*
@@ -44,23 +45,29 @@ pub fn handle(_server: &mut Server, game: &mut Game, steam_id: SteamId, mut pack
         params.remove("rot"),
     )
     else {
-        println!(
-            "[{TAG}] Invalid params in instance_actor packet: steam_id = {} params = {params:?}",
+        debug!(
+            target: TAG,
+            "Invalid params in instance_actor packet: steam_id = {} params = {params:?}",
             steam_id.raw()
         );
         return;
     };
     let actor_type = ActorType::from(actor_type.as_str());
-    if !game
-        .actor_manager
-        .user_can_create_actor(&steam_id, false, &actor_type)
+    let role = server.role(&steam_id);
+    if let Err(e) =
+        game.actor_manager
+            .user_can_create_actor(&steam_id, role, &actor_type, &server.config.props)
     {
-        println!(
-            "[{}] Blocked user creating an actor: steam_id = {}, packet = {:?}",
-            TAG,
+        debug!(
+            target: TAG,
+            "Blocked user creating an actor: steam_id = {}, error = {e:?}, packet = {:?}",
             steam_id.raw(),
             packet
         );
+        server.metrics.record_packet_rejected("blocked_actor");
+        if let Some(message) = e.chat_message() {
+            server.send_chat_message(&steam_id, &message);
+        }
         return;
     }
     let actor = Actor {
@@ -72,11 +79,20 @@ pub fn handle(_server: &mut Server, game: &mut Game, steam_id: SteamId, mut pack
         position: position,
         rotation: rotation,
     };
-    println!(
-        "[{}] Inserting new actor: steam_id = {} actor = {:?}",
-        TAG,
+    debug!(
+        target: TAG,
+        "Inserting new actor: steam_id = {} actor = {:?}",
         steam_id.raw(),
         actor
     );
-    game.actor_manager.insert_actor(actor);
+    let (actor_id, is_player) = (actor.id, actor.actor_type == ActorType::Player);
+    if !game.actor_manager.insert_actor(actor) {
+        server.metrics.record_packet_rejected("actor_id_collision");
+        return;
+    }
+    if is_player {
+        if let Some(player) = server.players.get_mut(&steam_id) {
+            player.actor_id = Some(actor_id);
+        }
+    }
 }
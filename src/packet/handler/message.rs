@@ -1,8 +1,13 @@
+use std::time::Instant;
+
+use log::info;
 use steamworks::SteamId;
 
 use crate::{
-    command::{handler::resolve_handler, CommandContext},
+    audit::AuditEvent,
+    command::{run_command, CommandContext, CommandSource},
     game::Game,
+    moderation::{apply_filter_violation, filter::FilterAction},
     packet::variant::{Dictionary, VariantValue},
     server::Server,
 };
@@ -13,27 +18,41 @@ pub fn handle(server: &mut Server, game: &mut Game, steam_id: SteamId, packet: D
     let Some(VariantValue::String(message)) = packet.get("message") else {
         return;
     };
-    println!(
-        "[{}] Received message from {}: {:?}",
-        TAG,
+    info!(
+        target: TAG,
+        "Received message from {} ({}): {:?}",
+        server.persona_name(&steam_id),
         steam_id.raw(),
         message
     );
     let stripped_message = message.replace("%u: ", "");
-    if stripped_message.starts_with('!') {
-        let command = &stripped_message.split_whitespace().next().unwrap()[1..];
-        let args = stripped_message
-            .split_whitespace()
-            .skip(1)
-            .collect::<Vec<_>>();
-
-        let command_context = CommandContext {
-            sender: steam_id,
-            command,
-            _args: args,
-        };
-        if let Some(handler) = resolve_handler(&command_context) {
-            handler(server, game, command_context);
+    server.record_event(AuditEvent::Chat {
+        steam_id: steam_id.raw(),
+        name: server.persona_name(&steam_id),
+        message: stripped_message.clone(),
+    });
+    if !server.is_admin(&steam_id) {
+        if let Some(violation) =
+            server
+                .chat_filter
+                .check(&steam_id, &stripped_message, Instant::now())
+        {
+            apply_filter_violation(server, game, &steam_id, &violation, &stripped_message);
+            if violation.action > FilterAction::Log {
+                return;
+            }
+        }
+    }
+    // Muted players are excluded from everything past this point.
+    if server.muted_steam_id(&steam_id) {
+        if stripped_message.starts_with('!') {
+            server.send_chat_message(&steam_id, "You are muted and cannot use commands.");
         }
+        return;
+    }
+    if let Some(command_ctx) =
+        CommandContext::parse(steam_id, CommandSource::Chat, &stripped_message)
+    {
+        run_command(server, game, command_ctx);
     }
 }
@@ -2,16 +2,20 @@ use steamworks::SteamId;
 
 use crate::{
     game::Game,
+    motd::send_motd,
     packet::{variant::Dictionary, P2pPacketTarget},
+    player::{announce_presence, Presence},
     Server,
 };
 
 /// Responds to a new_player_join packet.
 pub fn handle(server: &mut Server, game: &mut Game, steam_id: SteamId, _packet: Dictionary) {
-    server.send_chat_message(&steam_id, &server.config.motd);
-    // The real game would sync actors in the network loop with some per-actor class cooldown, but
-    // we only own actors which don't need updating (they don't move) so let's just sync them once
-    // when the player joins.
+    send_motd(server, &steam_id);
+    if let Some(player) = server.players.get(&steam_id) {
+        announce_presence(server, Presence::Join, &player.name, player.is_returning());
+    }
+    // Changes are synced in `ActorManager::on_update`, but the new player needs every host actor
+    // and its zone right away.
     game
         .actor_manager
         .sync_all_actors(server, P2pPacketTarget::SteamId(steam_id));
@@ -18,7 +18,7 @@ pub fn handle(server: &mut Server, game: &mut Game, steam_id: SteamId, _packet:
     );
     let actors = game
         .actor_manager
-        .get_actors_by_creator(&server.steam_client.user().steam_id());
+        .get_actors_by_creator(&server.host_steam_id);
     let mut list = Vec::new();
     for actor in actors {
         list.push(actor.clone_to_replication_variant_dict());
@@ -23,7 +23,7 @@ pub fn handle(server: &mut Server, _game: &mut Game, steam_id: SteamId, _packet:
     );
     response.insert(
         "from".to_owned(),
-        VariantValue::String(server.steam_client.user().steam_id().raw().to_string()),
+        VariantValue::String(server.host_steam_id.raw().to_string()),
     );
 
     send_variant_p2p(
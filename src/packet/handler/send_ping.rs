@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use steamworks::SteamId;
+
+use crate::{
+    game::Game,
+    packet::variant::{Dictionary, VariantValue},
+    time::system_time_since_unix_epoch_seconds_f64,
+    Server,
+};
+
+pub fn handle(server: &mut Server, _game: &mut Game, steam_id: SteamId, packet: Dictionary) {
+    /*
+    Packet format:
+    {
+        time: String,
+        from: String,
+    }
+    */
+    // `time` is echoed from our `request_ping` packet.
+    let sent_at = match packet.get("time") {
+        Some(VariantValue::String(time)) => time.parse::<f64>().ok(),
+        Some(VariantValue::Float(time)) => Some(*time),
+        _ => None,
+    };
+    let Some(sent_at) = sent_at else {
+        return;
+    };
+    let round_trip = system_time_since_unix_epoch_seconds_f64() - sent_at;
+    if !(0.0..60.0).contains(&round_trip) {
+        return;
+    }
+    if let Some(player) = server.players.get_mut(&steam_id) {
+        player.ping = Some(Duration::from_secs_f64(round_trip));
+    }
+}
@@ -1,15 +1,19 @@
 use std::{collections::HashSet, sync::mpsc::Sender};
 
+use log::error;
 use steamworks::{SendType, SteamId};
 
-use crate::game::actor::Actor;
+use crate::{game::actor::Actor, time::system_time_since_unix_epoch_seconds_f64};
 
 use super::{
     encode::encode_variant,
+    get_packet_type,
     variant::{Array, Dictionary, VariantValue, Vector3},
     OutgoingP2pPacketRequest, P2pChannel, P2pPacketTarget,
 };
 
+static TAG: &str = "packet::util";
+
 /// Builds a `message` packet. This packet represents a chat message.
 pub fn build_message_packet(message: &str) -> VariantValue {
     let mut packet = Dictionary::new();
@@ -72,6 +76,46 @@ pub fn build_force_disconnect_player_packet(user_id: &u64) -> VariantValue {
     VariantValue::Dictionary(packet)
 }
 
+/// Builds a `kick` packet. This packet is sent to the kicked user and makes them leave the lobby.
+pub fn build_kick_packet() -> VariantValue {
+    let mut packet = Dictionary::new();
+    packet.insert("type".to_owned(), VariantValue::String("kick".to_string()));
+
+    VariantValue::Dictionary(packet)
+}
+
+/// Builds a `ban` packet. This packet is sent to the banned user and makes them leave the lobby.
+pub fn build_ban_packet() -> VariantValue {
+    let mut packet = Dictionary::new();
+    packet.insert("type".to_owned(), VariantValue::String("ban".to_string()));
+
+    VariantValue::Dictionary(packet)
+}
+
+/// Builds a `peer_was_kicked` packet. This packet tells clients that the supplied user was kicked.
+pub fn build_peer_was_kicked_packet(user_id: u64) -> VariantValue {
+    let mut packet = Dictionary::new();
+    packet.insert(
+        "type".to_owned(),
+        VariantValue::String("peer_was_kicked".to_string()),
+    );
+    packet.insert("user_id".to_owned(), VariantValue::Int(user_id as i64));
+
+    VariantValue::Dictionary(packet)
+}
+
+/// Builds a `peer_was_banned` packet. This packet tells clients that the supplied user was banned.
+pub fn build_peer_was_banned_packet(user_id: u64) -> VariantValue {
+    let mut packet = Dictionary::new();
+    packet.insert(
+        "type".to_owned(),
+        VariantValue::String("peer_was_banned".to_string()),
+    );
+    packet.insert("user_id".to_owned(), VariantValue::Int(user_id as i64));
+
+    VariantValue::Dictionary(packet)
+}
+
 pub fn build_instance_actor_packet(actor: &Actor) -> VariantValue {
     let mut packet = Dictionary::new();
     packet.insert(
@@ -136,6 +180,26 @@ pub fn build_actor_request_packet(user_id: SteamId) -> VariantValue {
     VariantValue::Dictionary(packet)
 }
 
+/// Builds a `request_ping` packet. Clients answer with a `send_ping` packet echoing `time`, which
+/// is the current Unix time in seconds.
+pub fn build_request_ping_packet(host_id: SteamId) -> VariantValue {
+    let mut packet = Dictionary::new();
+    packet.insert(
+        "type".to_owned(),
+        VariantValue::String("request_ping".to_owned()),
+    );
+    packet.insert(
+        "time".to_owned(),
+        VariantValue::String(system_time_since_unix_epoch_seconds_f64().to_string()),
+    );
+    packet.insert(
+        "sender".to_owned(),
+        VariantValue::String(host_id.raw().to_string()),
+    );
+
+    VariantValue::Dictionary(packet)
+}
+
 pub fn build_weblobby_packet(member_list: &HashSet<u64>) -> VariantValue {
     let mut packet = Dictionary::new();
     packet.insert(
@@ -178,17 +242,22 @@ pub fn send_variant_p2p(
     channel: P2pChannel,
     send_type: SendType,
 ) {
+    let packet_type = match &variant {
+        VariantValue::Dictionary(dict) => get_packet_type(dict),
+        _ => "unknown".to_owned(),
+    };
     match encode_variant(variant) {
         Ok(data) => {
             let _ = sender.send(OutgoingP2pPacketRequest {
                 data,
+                packet_type,
                 target,
                 channel,
                 send_type,
             });
         }
         Err(err) => {
-            println!("Failed to encode variant: {}", err);
+            error!(target: TAG, "Failed to encode variant: {}", err);
         }
     }
 }
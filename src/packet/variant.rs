@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 type Float = f64;
 type Int = i64;
@@ -18,7 +18,7 @@ pub struct Rect2 {
     pub size: Vector2,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Vector3 {
     pub x: Float,
     pub y: Float,
@@ -0,0 +1,86 @@
+use std::{fs, io};
+
+use log::warn;
+use serde::{de::DeserializeOwned, Serialize};
+
+static TAG: &str = "persist";
+
+/// Reads a JSON file, which is named `what` in log messages. A missing file gives `T::default()`,
+/// so stores can start empty and create the file on their first change.
+///
+/// A file which cannot be read or parsed, such as after a typo in a hand edit, is renamed to
+/// `<path>.bak` before `T::default()` is returned, so the next save can't overwrite it. If it
+/// can't be moved aside either, this panics rather than risk losing its contents.
+pub fn load_json<T: DeserializeOwned + Default>(path: &str, what: &str) -> T {
+    match read_json(path) {
+        Ok(value) => value,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => T::default(),
+        Err(e) => {
+            let backup_path = format!("{path}.bak");
+            if let Err(rename_error) = fs::rename(path, &backup_path) {
+                panic!(
+                    "[{TAG}] Failed reading {what} and moving it aside: path = {path}, \
+                     error = {e}, rename_error = {rename_error}"
+                );
+            }
+            warn!(
+                target: TAG,
+                "Failed reading {what}, moved it aside and starting empty: path = {path}, \
+                 backup_path = {backup_path}, error = {e}"
+            );
+            T::default()
+        }
+    }
+}
+
+/// Writes the value to a JSON file, which is named `what` in log messages. The JSON is written to
+/// `<path>.tmp` first and then renamed over the file, so a crash mid-write leaves the old file
+/// intact. Errors are logged.
+pub fn save_json<T: Serialize>(path: &str, what: &str, value: &T) {
+    let temp_path = format!("{path}.tmp");
+    let result = serde_json::to_string_pretty(value)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+        .and_then(|json| fs::write(&temp_path, json))
+        .and_then(|_| fs::rename(&temp_path, path));
+    if let Err(e) = result {
+        warn!(
+            target: TAG,
+            "Failed writing {what}: path = {path}, error = {e}"
+        );
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &str) -> io::Result<T> {
+    serde_json::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use super::{load_json, save_json};
+    use crate::simulation::{remove_store_dir, temp_store_dir};
+
+    #[test]
+    fn unreadable_file_is_moved_aside() {
+        let store_dir = temp_store_dir("test-persist-invalid");
+        fs::create_dir_all(&store_dir).unwrap();
+        let path = store_dir.join("bans.json").to_string_lossy().into_owned();
+        fs::write(&path, "{ \"bans\": [").unwrap();
+
+        let loaded: HashMap<String, u64> = load_json(&path, "ban file");
+        assert!(loaded.is_empty());
+        assert_eq!(
+            fs::read_to_string(format!("{path}.bak")).unwrap(),
+            "{ \"bans\": ["
+        );
+
+        save_json(&path, "ban file", &HashMap::from([("a".to_owned(), 1u64)]));
+        let loaded: HashMap<String, u64> = load_json(&path, "ban file");
+        assert_eq!(loaded.get("a"), Some(&1));
+        assert!(!store_dir.join("bans.json.tmp").exists());
+
+        remove_store_dir(&store_dir);
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use steamworks::{SendType, SteamId};
+
+use crate::{
+    packet::{
+        util::{build_request_ping_packet, send_variant_p2p},
+        P2pChannel, P2pPacketTarget,
+    },
+    server::Server,
+    template::TemplateContext,
+    time::system_time_since_unix_epoch_seconds,
+};
+
+pub mod seen;
+
+/// How often players are sent a `request_ping` packet to measure their ping.
+static PING_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Host,
+    /// Listed in `config.admins`.
+    Admin,
+    /// Listed in `config.trusted`.
+    Trusted,
+    Player,
+}
+
+#[derive(Debug)]
+pub struct Player {
+    pub steam_id: SteamId,
+    /// The number shown by `!players` and used by `#number` targets. Players are numbered from 1
+    /// in the order they joined during this session, and a number is never reused. The host is 0.
+    pub number: u32,
+    /// The Steam persona name, updated whenever Steam reports a persona change.
+    pub name: String,
+    pub role: Role,
+    pub joined_at: Instant,
+    /// Unix timestamp in seconds of when the player joined.
+    pub joined_at_unix: u64,
+    /// The ID of the player's `player` actor, once it has been instanced.
+    pub actor_id: Option<i64>,
+    pub last_packet_at: Option<Instant>,
+    /// The round trip time of the last answered `request_ping`.
+    pub ping: Option<Duration>,
+    /// How many times the player has joined the lobby, including this time.
+    pub visits: u32,
+}
+
+impl Player {
+    /// Returns if the player has been in the lobby before.
+    pub fn is_returning(&self) -> bool {
+        self.visits > 1
+    }
+
+    pub fn session_duration(&self) -> Duration {
+        self.joined_at.elapsed()
+    }
+}
+
+/// The members of the lobby, including the host, in the order they joined.
+pub struct PlayerRegistry {
+    players: Vec<Player>,
+    next_number: u32,
+    next_ping_at: Instant,
+}
+
+impl PlayerRegistry {
+    pub fn new() -> Self {
+        Self {
+            players: vec![],
+            next_number: 1,
+            next_ping_at: Instant::now() + PING_INTERVAL,
+        }
+    }
+
+    /// Adds a player unless they are already registered.
+    pub fn insert(&mut self, steam_id: SteamId, name: String, role: Role, visits: u32) {
+        if self.contains(&steam_id) {
+            return;
+        }
+        let mut number = 0;
+        if role != Role::Host {
+            number = self.next_number;
+            self.next_number += 1;
+        }
+        self.players.push(Player {
+            steam_id,
+            number,
+            name,
+            role,
+            joined_at: Instant::now(),
+            joined_at_unix: system_time_since_unix_epoch_seconds(),
+            actor_id: None,
+            last_packet_at: None,
+            ping: None,
+            visits,
+        });
+    }
+
+    pub fn remove(&mut self, steam_id: &SteamId) -> Option<Player> {
+        let index = self
+            .players
+            .iter()
+            .position(|player| player.steam_id == *steam_id)?;
+
+        Some(self.players.remove(index))
+    }
+
+    pub fn contains(&self, steam_id: &SteamId) -> bool {
+        self.get(steam_id).is_some()
+    }
+
+    pub fn get(&self, steam_id: &SteamId) -> Option<&Player> {
+        self.players
+            .iter()
+            .find(|player| player.steam_id == *steam_id)
+    }
+
+    pub fn get_mut(&mut self, steam_id: &SteamId) -> Option<&mut Player> {
+        self.players
+            .iter_mut()
+            .find(|player| player.steam_id == *steam_id)
+    }
+
+    /// Returns all players in the order they joined.
+    pub fn iter(&self) -> impl Iterator<Item = &Player> {
+        self.players.iter()
+    }
+
+    /// Returns the players other than the host, in the order they joined.
+    pub fn listed(&self) -> Vec<&Player> {
+        self.players
+            .iter()
+            .filter(|player| player.role != Role::Host)
+            .collect()
+    }
+
+    /// Returns a player by their `!players` number.
+    pub fn get_by_number(&self, number: u32) -> Option<&Player> {
+        self.players
+            .iter()
+            .find(|player| player.role != Role::Host && player.number == number)
+    }
+
+    pub fn count(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Returns the raw SteamIds of all players, as used by the `receive_weblobby` packet.
+    pub fn steam_ids(&self) -> HashSet<u64> {
+        self.players
+            .iter()
+            .map(|player| player.steam_id.raw())
+            .collect()
+    }
+
+    pub fn record_packet(&mut self, steam_id: &SteamId, now: Instant) {
+        if let Some(player) = self.get_mut(steam_id) {
+            player.last_packet_at = Some(now);
+        }
+    }
+}
+
+/// Periodically asks every player for a ping. Replies are handled by the `send_ping` handler.
+pub fn on_update(server: &mut Server) {
+    let now = Instant::now();
+    if now < server.players.next_ping_at {
+        return;
+    }
+    server.players.next_ping_at = now + PING_INTERVAL;
+
+    send_variant_p2p(
+        &server.sender_p2p_packet,
+        build_request_ping_packet(server.host_steam_id),
+        P2pPacketTarget::All,
+        P2pChannel::GameState,
+        SendType::Unreliable,
+    );
+}
+
+/// A change in lobby membership which may be announced in chat.
+#[derive(Clone, Copy)]
+pub enum Presence {
+    Join,
+    Leave,
+    Kick,
+    Ban,
+}
+
+/// Tells the lobby that a player joined or left, if enabled in the `[join_leave]` config.
+/// Returning players are welcomed back instead of announced as joining if `welcome_back` is set.
+pub fn announce_presence(server: &Server, presence: Presence, name: &str, returning: bool) {
+    let config = &server.config.join_leave;
+    let (enabled, message) = match presence {
+        Presence::Join if returning && config.welcome_back => (true, &config.welcome_back_message),
+        Presence::Join => (config.join, &config.join_message),
+        Presence::Leave => (config.leave, &config.leave_message),
+        Presence::Kick => (config.kick, &config.kick_message),
+        Presence::Ban => (config.ban, &config.ban_message),
+    };
+    if enabled && !message.is_empty() {
+        let message = TemplateContext::new(server).with_name(name).render(message);
+        server.broadcast_chat_message(&message);
+    }
+}
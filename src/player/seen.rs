@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::persist::{load_json, save_json};
+
+static TAG: &str = "player::seen";
+
+/// When a player was first and last seen in the lobby.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Seen {
+    /// The SteamId as raw u64.
+    pub steam_id: u64,
+    /// The persona name during the last visit.
+    pub name: String,
+    /// Unix timestamp in seconds of the first visit.
+    pub first_seen: u64,
+    /// Unix timestamp in seconds of the last visit.
+    pub last_seen: u64,
+    pub visits: u32,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct SeenFile {
+    players: Vec<Seen>,
+}
+
+/// Every player who has joined the lobby, persisted to a JSON file similar to `BanList`.
+pub struct SeenList {
+    path: String,
+    players: HashMap<u64, Seen>,
+}
+
+impl SeenList {
+    /// Loads the seen list from the given path. If the file doesn't exist, an empty list is
+    /// returned which will create the file on the next visit. See `load_json` for files which
+    /// can't be read.
+    pub fn load(path: &str) -> Self {
+        let players = load_json::<SeenFile>(path, "seen file").players;
+        info!(target: TAG, "Loaded {} seen players: path = {path}", players.len());
+
+        Self {
+            path: path.to_owned(),
+            players: players
+                .into_iter()
+                .map(|seen| (seen.steam_id, seen))
+                .collect(),
+        }
+    }
+
+    /// Records a visit and saves the seen list. Returns the updated entry.
+    pub fn record_visit(&mut self, steam_id: u64, name: &str, now: u64) -> &Seen {
+        let seen = self.players.entry(steam_id).or_insert(Seen {
+            steam_id,
+            name: String::new(),
+            first_seen: now,
+            last_seen: now,
+            visits: 0,
+        });
+        seen.name = name.to_owned();
+        seen.last_seen = now;
+        seen.visits += 1;
+        self.save();
+
+        &self.players[&steam_id]
+    }
+
+    fn save(&self) {
+        let seen_file = SeenFile {
+            players: self.players.values().cloned().collect(),
+        };
+        save_json(&self.path, "seen file", &seen_file);
+    }
+}
@@ -1,27 +1,38 @@
 use std::ops::Range;
 
 use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
 
 const GODOT_RANDI_RANGE: Range<i64> = 0..i32::MAX as i64;
 static LOBBY_CODE_CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
 
+/// The random number generator which spawn decisions and actor IDs go through. The same seed and
+/// the same inputs give the same spawn sequence. Unlike `StdRng`, whose algorithm may change
+/// between rand versions, ChaCha8 gives the same numbers for a seed on every version and platform.
+pub type GameRng = ChaCha8Rng;
+
+/// Creates a `GameRng` from the given seed, or from a random seed if `None`. Returns the RNG and
+/// the seed, so it can be logged for replaying.
+pub fn game_rng(seed: Option<u64>) -> (GameRng, u64) {
+    let seed = seed.unwrap_or_else(|| thread_rng().gen());
+    (GameRng::seed_from_u64(seed), seed)
+}
+
 /// Mimics the Godot randi() function. Returns a random number between 0 and i32::MAX as an i64.
-pub fn godot_randi() -> i64 {
-    rand::thread_rng().gen_range(GODOT_RANDI_RANGE)
+pub fn godot_randi(rng: &mut impl Rng) -> i64 {
+    rng.gen_range(GODOT_RANDI_RANGE)
 }
 
-pub fn godot_rand_range(start: f64, end: f64) -> f64 {
-    rand::thread_rng().gen_range(start..end)
+pub fn godot_rand_range(rng: &mut impl Rng, start: f64, end: f64) -> f64 {
+    rng.gen_range(start..end)
 }
 
-pub fn godot_randf() -> f64 {
-    godot_rand_range(0.0, 1.0)
+pub fn godot_randf(rng: &mut impl Rng) -> f64 {
+    godot_rand_range(rng, 0.0, 1.0)
 }
 
 /// Returns a six-digit alphanumeric code
-pub fn lobby_code() -> String {
-    let mut rng = thread_rng();
-    
+pub fn lobby_code(rng: &mut impl Rng) -> String {
     (0..6)
         .map(|_| {
             let idx = rng.gen_range(0..LOBBY_CODE_CHARSET.len());
@@ -35,3 +46,19 @@ pub fn lobby_code() -> String {
 // pub fn lobby_server_browser_value() -> String {
 //     rand::thread_rng().gen_range(0..20).to_string()
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::{game_rng, godot_randi};
+
+    #[test]
+    fn same_seed_gives_same_numbers() {
+        let (mut first, seed) = game_rng(Some(42));
+        let (mut second, _) = game_rng(Some(42));
+
+        assert_eq!(seed, 42);
+        for _ in 0..16 {
+            assert_eq!(godot_randi(&mut first), godot_randi(&mut second));
+        }
+    }
+}
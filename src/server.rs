@@ -1,23 +1,61 @@
-use std::{collections::HashSet, sync::mpsc::Sender};
+use std::{io, sync::mpsc::Sender, time::Instant};
 
+use log::info;
 use steamworks::{Client, LobbyId, SendType, SteamId};
 
-use crate::{config::Config, packet::{
-    util::{build_message_packet, send_variant_p2p},
-    OutgoingP2pPacketRequest, P2pChannel, P2pPacketTarget,
-}};
+use crate::{
+    announcement::AnnouncementScheduler,
+    audit::{AuditEvent, AuditLog},
+    config::Config,
+    event::EventBus,
+    metrics::Metrics,
+    motd::MessageQueue,
+    moderation::{
+        ban::BanList,
+        filter::ChatFilter,
+        mute::MuteList,
+        vote_kick::VoteKickManager,
+    },
+    packet::{
+        util::{build_message_packet, send_variant_p2p},
+        OutgoingP2pPacketRequest, P2pChannel, P2pPacketTarget,
+    },
+    player::{seen::SeenList, PlayerRegistry, Role},
+    time::system_time_since_unix_epoch_seconds,
+};
+
+static TAG: &str = "server";
 
 pub struct Server {
-    pub steam_client: Client,
+    /// `None` when running headless, such as for `--simulate`. See `Server::steam`.
+    pub steam_client: Option<Client>,
+    /// The SteamId of the account Ducky runs as, which owns the lobby and the host actors.
+    pub host_steam_id: SteamId,
     pub sender_p2p_packet: Sender<OutgoingP2pPacketRequest>,
     // TODO: Holding lobby_id here means we can't have multiple lobbies open at once.
     pub lobby_id: Option<LobbyId>,
     // TODO: Holding ban_list here means we can't have per-lobby ban lists.
-    /// A list of banned SteamIds as raw u64.
-    pub ban_list: HashSet<u64>,
+    /// Active bans, persisted to `config.ban_file`.
+    pub ban_list: BanList,
+    /// Active mutes, persisted to `config.mute_file`.
+    pub mute_list: MuteList,
+    /// Every player who has joined, persisted to `config.seen_file`.
+    pub seen_list: SeenList,
+    pub chat_filter: ChatFilter,
+    pub vote_kick: VoteKickManager,
+    pub audit_log: AuditLog,
+    pub announcements: AnnouncementScheduler,
+    /// Streams events to HTTP API subscribers.
+    pub events: EventBus,
+    pub metrics: Metrics,
     pub config: Config,
-    /// A list of users in the lobby.
-    pub users: HashSet<u64>,
+    /// The members of the lobby, including the host.
+    pub players: PlayerRegistry,
+    /// Chat messages which will be sent after a delay.
+    pub message_queue: MessageQueue,
+    pub started_at: Instant,
+    /// Set to stop the server at the end of the current tick.
+    pub shutdown_requested: bool,
 }
 
 impl Server {
@@ -25,29 +63,179 @@ impl Server {
         client: Client,
         sender_p2p_packet: Sender<OutgoingP2pPacketRequest>,
         config: Config,
+    ) -> Self {
+        let host_steam_id = client.user().steam_id();
+        Self::with_steam_client(Some(client), host_steam_id, sender_p2p_packet, config)
+    }
+
+    /// Creates a server without a Steam client, which acts as the given host. Only code which
+    /// doesn't talk to Steam, such as the game logic, can be used.
+    pub fn headless(
+        host_steam_id: SteamId,
+        sender_p2p_packet: Sender<OutgoingP2pPacketRequest>,
+        config: Config,
+    ) -> Self {
+        Self::with_steam_client(None, host_steam_id, sender_p2p_packet, config)
+    }
+
+    fn with_steam_client(
+        steam_client: Option<Client>,
+        host_steam_id: SteamId,
+        sender_p2p_packet: Sender<OutgoingP2pPacketRequest>,
+        config: Config,
     ) -> Self {
         Self {
-            steam_client: client,
+            steam_client,
+            host_steam_id,
             sender_p2p_packet,
             lobby_id: None,
-            ban_list: HashSet::new(),
+            ban_list: BanList::load(&config.ban_file, &config.ban_list),
+            mute_list: MuteList::load(&config.mute_file),
+            seen_list: SeenList::load(&config.seen_file),
+            chat_filter: ChatFilter::new(&config.chat_filter),
+            vote_kick: VoteKickManager::new(),
+            audit_log: AuditLog::new(&config.audit_log),
+            announcements: AnnouncementScheduler::new(&config.announcements),
+            events: EventBus::new(),
+            metrics: Metrics::new(),
             config,
-            users: HashSet::new(),
+            players: PlayerRegistry::new(),
+            message_queue: MessageQueue::new(),
+            started_at: Instant::now(),
+            shutdown_requested: false,
         }
     }
 
+    /// Returns the Steam client. Panics on a headless server, which never runs the lobby and
+    /// networking code that calls this.
+    pub fn steam(&self) -> &Client {
+        self.steam_client
+            .as_ref()
+            .expect("Steam client is not available on a headless server")
+    }
+
     pub fn set_lobby_id(&mut self, lobby_id: LobbyId) {
         self.lobby_id = Some(lobby_id);
     }
 
-    pub fn insert_ban_list(&mut self, steam_id: u64) {
-        self.ban_list.insert(steam_id);
+    /// Records an event in the audit log and publishes it to event subscribers.
+    pub fn record_event(&mut self, event: AuditEvent) {
+        self.events.publish(&event);
+        self.audit_log.record(event);
+    }
+
+    /// Reads `config.toml` again and applies it. Fields which are only used during startup, such as
+    /// `ban_file` or `max_players` for lobby creation, won't take effect until a restart. The lobby
+    /// code stays the same unless the file sets one.
+    pub fn reload_config(&mut self) -> io::Result<()> {
+        let config = Config::load(Some(&self.config.lobby_code))?;
+        self.chat_filter.set_config(&config.chat_filter);
+        self.audit_log.set_config(&config.audit_log);
+        self.announcements.set_config(&config.announcements);
+        self.ban_list.set_config_bans(&config.ban_list);
+        self.config = config;
+        self.update_lobby_ban_list();
+        // Admins may have been added or removed.
+        let roles: Vec<(SteamId, Role)> = self
+            .players
+            .iter()
+            .map(|player| (player.steam_id, self.role(&player.steam_id)))
+            .collect();
+        for (steam_id, role) in roles {
+            if let Some(player) = self.players.get_mut(&steam_id) {
+                player.role = role;
+            }
+        }
+
+        Ok(())
     }
 
     pub fn banned_steam_id(&self, steam_id: &SteamId) -> bool {
         self.ban_list.contains(&steam_id.raw())
     }
 
+    pub fn muted_steam_id(&self, steam_id: &SteamId) -> bool {
+        self.mute_list.contains(&steam_id.raw())
+    }
+
+    /// Returns the Steam persona name of the given SteamId. Names of players in the lobby are
+    /// taken from the player registry.
+    pub fn persona_name(&self, steam_id: &SteamId) -> String {
+        match self.players.get(steam_id) {
+            Some(player) => player.name.clone(),
+            None => self.steam().friends().get_friend(*steam_id).name(),
+        }
+    }
+
+    /// Adds a member to the player registry and records their visit in the seen list. Steam is
+    /// asked for their persona name, which may not be known yet. `refresh_persona_name` updates it
+    /// once Steam has it.
+    pub fn insert_player(&mut self, steam_id: SteamId) {
+        if self.players.contains(&steam_id) {
+            return;
+        }
+        let friends = self.steam().friends();
+        friends.request_user_information(steam_id, true);
+        let name = friends.get_friend(steam_id).name();
+        let role = self.role(&steam_id);
+        let visits = self
+            .seen_list
+            .record_visit(steam_id.raw(), &name, system_time_since_unix_epoch_seconds())
+            .visits;
+        info!(
+            target: TAG,
+            "Added player: steam_id = {}, name = {name}, role = {role:?}, visits = {visits}",
+            steam_id.raw()
+        );
+        self.players.insert(steam_id, name, role, visits);
+    }
+
+    /// Updates a player's name after Steam reports a persona change.
+    pub fn refresh_persona_name(&mut self, steam_id: &SteamId) {
+        let name = self.steam().friends().get_friend(*steam_id).name();
+        let Some(player) = self.players.get_mut(steam_id) else {
+            return;
+        };
+        if player.name != name {
+            info!(
+                target: TAG,
+                "Player name changed: steam_id = {}, old = {}, new = {name}",
+                steam_id.raw(),
+                player.name
+            );
+            player.name = name;
+        }
+    }
+
+    pub fn role(&self, steam_id: &SteamId) -> Role {
+        if *steam_id == self.host_steam_id {
+            Role::Host
+        } else if self.config.admins.contains(&steam_id.raw()) {
+            Role::Admin
+        } else if self.config.trusted.contains(&steam_id.raw()) {
+            Role::Trusted
+        } else {
+            Role::Player
+        }
+    }
+
+    /// Returns if the given SteamId is the lobby host or listed in `config.admins`.
+    pub fn is_admin(&self, steam_id: &SteamId) -> bool {
+        *steam_id == self.host_steam_id || self.config.admins.contains(&steam_id.raw())
+    }
+
+    /// Updates the `banned_players` lobby field from the ban list. Clients use this field to leave
+    /// lobbies they are banned from.
+    pub fn update_lobby_ban_list(&self) {
+        if let Some(lobby_id) = self.lobby_id {
+            self.steam().matchmaking().set_lobby_data(
+                lobby_id,
+                "banned_players",
+                &self.ban_list.to_lobby_data(),
+            );
+        }
+    }
+
     // This is a utility function for sending a packet, does this belong here?
     pub fn send_chat_message(&self, steam_id: &SteamId, message: &str) {
         send_variant_p2p(
@@ -58,4 +246,15 @@ impl Server {
             SendType::Reliable,
         );
     }
+
+    /// Sends a chat message to everyone in the lobby.
+    pub fn broadcast_chat_message(&self, message: &str) {
+        send_variant_p2p(
+            &self.sender_p2p_packet,
+            build_message_packet(message),
+            P2pPacketTarget::All,
+            P2pChannel::GameState,
+            SendType::Reliable,
+        );
+    }
 }
@@ -0,0 +1,166 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs, io,
+    path::{Path, PathBuf},
+    process,
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+use steamworks::SteamId;
+
+use crate::{
+    config::Config,
+    game::{clock::Clock, Game},
+    packet::OutgoingP2pPacketRequest,
+    server::Server,
+    time::format_duration,
+    TICK_MS,
+};
+
+static TAG: &str = "simulation";
+/// How much simulated time passes between progress logs.
+static REPORT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// The host the simulation acts as, since there is no Steam account.
+static HOST_STEAM_ID: u64 = 76561197960265728;
+
+/// Runs the game without Steam or a lobby for the given simulated duration, advancing a manual
+/// clock one tick at a time as fast as possible. Packets are dropped instead of sent. Logs the
+/// actor counts as it goes and checks that every spawn was either despawned or is still alive at
+/// the end, and that the actor indexes agree.
+pub fn run(config: Config, duration: Duration) {
+    info!(
+        target: TAG,
+        "Simulating {} of spawns without a lobby",
+        format_duration(duration)
+    );
+    let store_dir = temp_store_dir("simulation");
+    let (mut server, receiver_p2p_packet) = headless_server(config, &store_dir);
+    let mut game = Game::new(&server.config, Clock::manual());
+    game.on_ready(&mut server);
+
+    let tick = Duration::from_millis(TICK_MS as u64);
+    let started_at = Instant::now();
+    let mut simulated = Duration::ZERO;
+    let mut next_report = REPORT_INTERVAL;
+    let mut packets = 0;
+    while simulated < duration {
+        game.clock.advance(tick);
+        simulated += tick;
+        game.on_update(&mut server);
+        packets += receiver_p2p_packet.try_iter().count();
+
+        if simulated >= next_report {
+            next_report += REPORT_INTERVAL;
+            info!(
+                target: TAG,
+                "{}: actors = {}, {}",
+                format_duration(simulated),
+                game.actor_manager.count_actors(),
+                format_counts(&alive_counts(&game))
+            );
+        }
+    }
+
+    info!(
+        target: TAG,
+        "Simulated {} in {}: packets = {packets}",
+        format_duration(simulated),
+        format_duration(started_at.elapsed())
+    );
+    let alive = alive_counts(&game);
+    let spawns = server.metrics.spawns();
+    let despawns = server.metrics.despawns();
+    let actor_types: BTreeSet<&String> = spawns.keys().chain(despawns.keys()).collect();
+    let mut consistent = true;
+    for actor_type in actor_types {
+        let spawned = spawns.get(actor_type).copied().unwrap_or(0);
+        let despawned = despawns.get(actor_type).copied().unwrap_or(0);
+        let alive = alive.get(actor_type).copied().unwrap_or(0);
+        info!(
+            target: TAG,
+            "{actor_type}: spawned = {spawned}, despawned = {despawned}, alive = {alive}"
+        );
+        if spawned != despawned + alive {
+            consistent = false;
+            warn!(
+                target: TAG,
+                "Spawns and despawns don't add up: actor_type = {actor_type}, missing = {}",
+                spawned as i64 - despawned as i64 - alive as i64
+            );
+        }
+    }
+    for problem in game.actor_manager.check_consistency() {
+        consistent = false;
+        warn!(target: TAG, "Actor index inconsistency: {problem}");
+    }
+    if consistent {
+        info!(
+            target: TAG,
+            "Every spawn was despawned or is still alive and the actor indexes agree"
+        );
+    }
+
+    drop(server);
+    remove_store_dir(&store_dir);
+}
+
+/// Returns a directory for the stores of a `headless_server`, unique to this process.
+pub fn temp_store_dir(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("ducky-{name}-{}", process::id()))
+}
+
+/// Creates a server without Steam which keeps its bans, mutes, seen players and audit log in
+/// `store_dir`, so the real files are never touched. Returns it along with the receiver of the
+/// packets it sends.
+pub fn headless_server(
+    mut config: Config,
+    store_dir: &Path,
+) -> (Server, Receiver<OutgoingP2pPacketRequest>) {
+    config.ban_file = store_path(store_dir, "bans.json");
+    config.mute_file = store_path(store_dir, "mutes.json");
+    config.seen_file = store_path(store_dir, "seen.json");
+    config.audit_log.directory = store_path(store_dir, "logs");
+    let (sender_p2p_packet, receiver_p2p_packet) = mpsc::channel::<OutgoingP2pPacketRequest>();
+    let server = Server::headless(SteamId::from_raw(HOST_STEAM_ID), sender_p2p_packet, config);
+
+    (server, receiver_p2p_packet)
+}
+
+/// Removes the directory of a `headless_server` once the server is dropped.
+pub fn remove_store_dir(store_dir: &Path) {
+    if let Err(e) = fs::remove_dir_all(store_dir) {
+        if e.kind() != io::ErrorKind::NotFound {
+            warn!(
+                target: TAG,
+                "Failed removing simulation files: path = {}, error = {e}",
+                store_dir.display()
+            );
+        }
+    }
+}
+
+fn store_path(store_dir: &Path, name: &str) -> String {
+    store_dir.join(name).to_string_lossy().into_owned()
+}
+
+/// Returns the number of actors of each type which are alive.
+fn alive_counts(game: &Game) -> BTreeMap<String, u64> {
+    let mut counts = BTreeMap::new();
+    for actor in game.actor_manager.get_all_actors() {
+        *counts
+            .entry(String::from(actor.actor_type.clone()))
+            .or_default() += 1;
+    }
+
+    counts
+}
+
+fn format_counts(counts: &BTreeMap<String, u64>) -> String {
+    counts
+        .iter()
+        .map(|(actor_type, count)| format!("{actor_type} = {count}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
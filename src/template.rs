@@ -0,0 +1,95 @@
+use std::time::{Duration, Instant};
+
+use crate::{server::Server, time::format_duration};
+
+/// Values for the `{placeholder}`s in a chat message template, such as the MOTD or join messages.
+/// Placeholders without a value are left as they are.
+pub struct TemplateContext {
+    values: Vec<(&'static str, String)>,
+}
+
+impl TemplateContext {
+    /// Creates a context with the placeholders which describe the lobby: `{players}`,
+    /// `{max_players}`, `{lobby_code}`, `{uptime}` and `{rules_url}`.
+    pub fn new(server: &Server) -> Self {
+        let config = &server.config;
+        Self {
+            values: vec![
+                ("players", server.players.listed().len().to_string()),
+                ("max_players", config.max_players.to_string()),
+                ("lobby_code", config.lobby_code.clone()),
+                (
+                    "uptime",
+                    format_duration(Instant::now().duration_since(server.started_at)),
+                ),
+                ("rules_url", config.rules_url.clone()),
+            ],
+        }
+    }
+
+    /// Sets `{name}` to the name of the player the message is about.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.values.push(("name", name.to_owned()));
+        self
+    }
+
+    /// Sets `{duration}` to how long something lasts, such as a spawn event.
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.values.push(("duration", format_duration(duration)));
+        self
+    }
+
+    pub fn render(&self, template: &str) -> String {
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let Some(end) = rest.find('}') else {
+                break;
+            };
+            let key = &rest[1..end];
+            match self.values.iter().find(|(name, _)| *name == key) {
+                Some((_, value)) => rendered.push_str(value),
+                None => rendered.push_str(&rest[..=end]),
+            }
+            rest = &rest[end + 1..];
+        }
+        rendered.push_str(rest);
+
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::TemplateContext;
+
+    fn context() -> TemplateContext {
+        TemplateContext {
+            values: vec![
+                ("players", "3".to_owned()),
+                ("max_players", "12".to_owned()),
+            ],
+        }
+        .with_name("Ducky")
+        .with_duration(Duration::from_secs(90))
+    }
+
+    #[test]
+    fn render_replaces_placeholders() {
+        assert_eq!(
+            context().render("Hi {name}! {players}/{max_players} here, ends in {duration}."),
+            "Hi Ducky! 3/12 here, ends in 1m 30s."
+        );
+    }
+
+    #[test]
+    fn render_keeps_unknown_and_unclosed_placeholders() {
+        assert_eq!(context().render("{unknown} {name}"), "{unknown} Ducky");
+        assert_eq!(context().render("{} {{name}}"), "{} {{name}}");
+        assert_eq!(context().render("open {name"), "open {name");
+    }
+}
@@ -1,4 +1,4 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub fn system_time_since_unix_epoch_seconds() -> u64 {
     SystemTime::now()
@@ -13,3 +13,127 @@ pub fn system_time_since_unix_epoch_seconds_f64() -> f64 {
         .unwrap()
         .as_secs_f64()
 }
+
+/// Parses a duration in the form of `<number><unit>`, where unit is one of `s`, `m`, `h`, `d` or
+/// `w`. A bare number is treated as minutes. Zero is rejected, since a zero-length tempban or event
+/// would end on the next tick.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim().to_lowercase();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value.as_str(), "m"),
+    };
+    let number: u64 = number.parse().ok().filter(|number| *number > 0)?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        "w" => 60 * 60 * 24 * 7,
+        _ => return None,
+    };
+
+    number.checked_mul(multiplier).map(Duration::from_secs)
+}
+
+/// Parses a time of day in the form of `HH:MM`. Returns the number of minutes since midnight.
+pub fn parse_time_of_day(value: &str) -> Option<u32> {
+    let (hour, minute) = value.trim().split_once(':')?;
+    let (hour, minute): (u32, u32) = (hour.parse().ok()?, minute.parse().ok()?);
+    if hour >= 24 || minute >= 60 {
+        return None;
+    }
+
+    Some(hour * 60 + minute)
+}
+
+/// Formats a duration as a short human readable string, e.g. `1h 2m 3s`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else if secs < 86400 {
+        format!("{}h {}m {}s", secs / 3600, (secs % 3600) / 60, secs % 60)
+    } else {
+        format!("{}d {}h {}m", secs / 86400, (secs % 86400) / 3600, (secs % 3600) / 60)
+    }
+}
+
+/// Converts a Unix timestamp in seconds to a UTC (year, month, day, hour, minute, second) tuple.
+pub fn utc_datetime_from_unix_seconds(secs: u64) -> (i64, u32, u32, u32, u32, u32) {
+    let days = (secs / 86400) as i64;
+    let secs_of_day = secs % 86400;
+    // Howard Hinnant's civil_from_days algorithm.
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (
+        year,
+        month,
+        day,
+        (secs_of_day / 3600) as u32,
+        (secs_of_day % 3600 / 60) as u32,
+        (secs_of_day % 60) as u32,
+    )
+}
+
+/// Formats a Unix timestamp in seconds as an RFC 3339 UTC timestamp, e.g. `2024-12-01T13:37:00Z`.
+pub fn format_rfc3339_utc(secs: u64) -> String {
+    let (year, month, day, hour, minute, second) = utc_datetime_from_unix_seconds(secs);
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z")
+}
+
+/// Formats the UTC date of a Unix timestamp in seconds, e.g. `2024-12-01`.
+pub fn format_utc_date(secs: u64) -> String {
+    let (year, month, day, ..) = utc_datetime_from_unix_seconds(secs);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::parse_duration;
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("15m"), Some(Duration::from_secs(15 * 60)));
+        assert_eq!(parse_duration("2H"), Some(Duration::from_secs(2 * 60 * 60)));
+        assert_eq!(
+            parse_duration(" 1d "),
+            Some(Duration::from_secs(60 * 60 * 24))
+        );
+        assert_eq!(
+            parse_duration("1w"),
+            Some(Duration::from_secs(60 * 60 * 24 * 7))
+        );
+    }
+
+    #[test]
+    fn parse_duration_defaults_to_minutes() {
+        assert_eq!(parse_duration("10"), Some(Duration::from_secs(10 * 60)));
+    }
+
+    #[test]
+    fn parse_duration_rejects_invalid_values() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("5y"), None);
+        assert_eq!(parse_duration("1.5h"), None);
+        assert_eq!(parse_duration("-5m"), None);
+        assert_eq!(parse_duration("0"), None);
+        assert_eq!(parse_duration("0s"), None);
+        assert_eq!(parse_duration(&format!("{}w", u64::MAX)), None);
+    }
+}